@@ -30,6 +30,8 @@ pub fn run(args: &ClientArgs<'_>) {
     let context = Arc::new(HandlerContext {
         codechain_address: args.codechain_address,
         name: args.name.to_string(),
+        hub_key: args.hub_key.map(str::to_string),
+        hub_ca: args.hub_ca.map(str::to_string),
         process,
         hardware_service,
     });
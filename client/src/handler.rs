@@ -3,10 +3,20 @@ use super::types::HandlerContext;
 use jsonrpc_core::types::{
     Call, Error as JSONRPCError, ErrorCode, Failure, Id, MethodCall, Response, Success, Version,
 };
+use openssl::ssl::{SslConnector, SslMethod, SslStream};
+use openssl::x509::store::{X509Store, X509StoreBuilder};
+use openssl::x509::X509;
 use std::cell::Cell;
+use std::fs;
 use std::rc::Rc;
 use std::sync::Arc;
-use ws::{CloseCode, Error as WSError, Handler, Handshake, Message, Result, Sender};
+use url::Url;
+use ws::util::TcpStream;
+use ws::{CloseCode, Error as WSError, ErrorKind, Handler, Handshake, Message, Request, Result, Sender};
+
+/// The handshake headers that Agent Hub authenticates this node with.
+const NODE_NAME_HEADER: &str = "X-Node-Name";
+const NODE_KEY_HEADER: &str = "X-Node-Key";
 
 pub struct WebSocketHandler {
     pub out: Sender,
@@ -16,6 +26,26 @@ pub struct WebSocketHandler {
 }
 
 impl Handler for WebSocketHandler {
+    fn build_request(&mut self, url: &Url) -> Result<Request> {
+        let mut request = Request::from_url(url)?;
+        let headers = request.headers_mut();
+        headers.push((NODE_NAME_HEADER.to_string(), self.context.name.clone().into_bytes()));
+        if let Some(hub_key) = &self.context.hub_key {
+            headers.push((NODE_KEY_HEADER.to_string(), hub_key.clone().into_bytes()));
+        }
+        Ok(request)
+    }
+
+    /// Called for a "wss://" URL. Only the certificates in `hub_ca` are trusted if it is set.
+    fn upgrade_ssl_client(&mut self, sock: TcpStream, url: &Url) -> Result<SslStream<TcpStream>> {
+        let host = url.host_str().ok_or_else(|| WSError::new(ErrorKind::Internal, "No host in the Agent Hub URL"))?;
+        let mut builder = SslConnector::builder(SslMethod::tls()).map_err(ssl_error)?;
+        if let Some(hub_ca) = &self.context.hub_ca {
+            builder.set_cert_store(load_cert_store(hub_ca)?);
+        }
+        builder.build().connect(host, sock).map_err(From::from)
+    }
+
     fn on_open(&mut self, _: Handshake) -> Result<()> {
         // We have a new connection, so we increment the connection counter
         self.count.set(self.count.get() + 1);
@@ -134,3 +164,16 @@ impl Handler for WebSocketHandler {
         cerror!(WEB, "The server encountered an error: {:?}", err);
     }
 }
+
+fn load_cert_store(path: &str) -> Result<X509Store> {
+    let pem = fs::read(path)?;
+    let mut builder = X509StoreBuilder::new().map_err(ssl_error)?;
+    for cert in X509::stack_from_pem(&pem).map_err(ssl_error)? {
+        builder.add_cert(cert).map_err(ssl_error)?;
+    }
+    Ok(builder.build())
+}
+
+fn ssl_error(err: openssl::error::ErrorStack) -> WSError {
+    WSError::new(ErrorKind::Internal, format!("Cannot set up TLS: {}", err))
+}
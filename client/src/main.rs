@@ -9,6 +9,7 @@ mod types;
 
 use self::client::run;
 use clap::load_yaml;
+use std::env;
 use types::ClientArgs;
 
 fn main() {
@@ -18,6 +19,8 @@ fn main() {
     let codechain_dir = matches.value_of("codechain-dir").expect("codechain-dir is required option");
     let log_file_path = matches.value_of("log-file").unwrap_or("codechain.log");
     let hub_url = matches.value_of("agent-hub-url").expect("agent-hub-url is required option");
+    let hub_ca = matches.value_of("agent-hub-ca");
+    let hub_key = matches.value_of("agent-hub-key").map(str::to_string).or_else(|| env::var("AGENT_HUB_KEY").ok());
     let codechain_address =
         matches.value_of("codechain-p2p-address").expect("codechain-p2p-address is required option");
     let codechain_address = codechain_address.parse().expect("codechain-p2p-address field's format is invalid");
@@ -27,6 +30,8 @@ fn main() {
         codechain_dir,
         log_file_path,
         hub_url,
+        hub_key: hub_key.as_deref(),
+        hub_ca,
         codechain_address,
         name,
     };
@@ -9,6 +9,8 @@ pub struct ClientArgs<'a> {
     pub codechain_dir: &'a str,
     pub log_file_path: &'a str,
     pub hub_url: &'a str,
+    pub hub_key: Option<&'a str>,
+    pub hub_ca: Option<&'a str>,
     pub codechain_address: IpAddr,
     pub name: &'a str,
 }
@@ -17,5 +19,7 @@ pub struct HandlerContext {
     pub process: Sender<ProcessMessage>,
     pub codechain_address: IpAddr,
     pub name: String,
+    pub hub_key: Option<String>,
+    pub hub_ca: Option<String>,
     pub hardware_service: HardwareService,
 }
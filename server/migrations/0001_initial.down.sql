@@ -0,0 +1,8 @@
+DROP MATERIALIZED VIEW IF EXISTS time_5min_peer_report_view_materialized;
+DROP MATERIALIZED VIEW IF EXISTS time_5min_extension_report_view_materialized;
+DROP MATERIALIZED VIEW IF EXISTS time_5min_avg_report_view_materialized;
+DROP MATERIALIZED VIEW IF EXISTS time_5min_report_view_materialized;
+DROP TABLE IF EXISTS network_usage;
+DROP TABLE IF EXISTS peer_count;
+DROP TABLE IF EXISTS logs;
+DROP TABLE IF EXISTS client_extra;
@@ -0,0 +1,81 @@
+CREATE TABLE IF NOT EXISTS client_extra (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    prev_env VARCHAR NOT NULL,
+    prev_args VARCHAR NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS logs (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    level VARCHAR NOT NULL,
+    target VARCHAR NOT NULL,
+    thread_name VARCHAR NOT NULL,
+    message VARCHAR NOT NULL,
+    timestamp TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX IF NOT EXISTS logs_timestamp ON logs (timestamp);
+CREATE INDEX IF NOT EXISTS logs_targets ON logs (target);
+
+CREATE TABLE IF NOT EXISTS peer_count (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMP WITH TIME ZONE NOT NULL,
+    name VARCHAR NOT NULL,
+    peer_count INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS peer_count_time_index ON peer_count (name, time);
+
+CREATE TABLE IF NOT EXISTS network_usage (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMP WITH TIME ZONE NOT NULL,
+    name VARCHAR NOT NULL,
+    extension VARCHAR NOT NULL,
+    target_ip VARCHAR NOT NULL,
+    bytes INTEGER NOT NULL,
+    time_5min TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_hour TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_day TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX IF NOT EXISTS network_usage_time_index ON network_usage (time);
+CREATE INDEX IF NOT EXISTS network_usage_time_5min_index ON network_usage (name, time_5min);
+CREATE INDEX IF NOT EXISTS network_usage_time_hour_index ON network_usage (name, time_hour);
+CREATE INDEX IF NOT EXISTS network_usage_time_day_index ON network_usage (name, time_day);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS time_5min_report_view_materialized AS SELECT
+    name,
+    time_5min,
+    CAST (SUM(bytes) AS REAL) AS value
+    FROM network_usage
+    WHERE time_5min > NOW() - interval '7 days'
+    GROUP BY name, time_5min
+    ORDER BY name, time_5min;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS time_5min_avg_report_view_materialized AS SELECT
+    network_usage.name,
+    time_5min,
+    CAST (SUM(bytes/greatest(peer_count.peer_count, 1)) AS REAL) AS value
+    FROM network_usage
+    LEFT JOIN peer_count ON (network_usage.time=peer_count.time AND network_usage.name=peer_count.name)
+    WHERE time_5min > NOW() - interval '7 days'
+    GROUP BY network_usage.name, time_5min
+    ORDER BY network_usage.name, time_5min;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS time_5min_extension_report_view_materialized AS SELECT
+    name,
+    extension,
+    time_5min,
+    CAST (SUM(bytes) AS REAL) AS value
+    FROM network_usage
+    WHERE time_5min > NOW() - interval '7 days'
+    GROUP BY name, time_5min, network_usage.extension
+    ORDER BY name, time_5min, network_usage.extension;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS time_5min_peer_report_view_materialized AS SELECT
+    name,
+    target_ip,
+    time_5min,
+    CAST (SUM(bytes) AS REAL) AS value
+    FROM network_usage
+    WHERE time_5min > NOW() - interval '7 days'
+    GROUP BY name, time_5min, network_usage.target_ip
+    ORDER BY name, time_5min, network_usage.target_ip;
@@ -0,0 +1,7 @@
+CREATE TABLE node_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    status VARCHAR NOT NULL,
+    time TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX node_status_history_name_time_index ON node_status_history (name, time);
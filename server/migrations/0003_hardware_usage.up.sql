@@ -0,0 +1,12 @@
+CREATE TABLE hardware_usage (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMP WITH TIME ZONE NOT NULL,
+    name VARCHAR NOT NULL,
+    metric VARCHAR NOT NULL,
+    value REAL NOT NULL,
+    time_5min TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_hour TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_day TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX hardware_usage_time_index ON hardware_usage (time);
+CREATE INDEX hardware_usage_name_time_index ON hardware_usage (name, time);
@@ -0,0 +1,11 @@
+CREATE TABLE best_block (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMP WITH TIME ZONE NOT NULL,
+    name VARCHAR NOT NULL,
+    number BIGINT NOT NULL,
+    time_5min TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_hour TIMESTAMP WITH TIME ZONE NOT NULL,
+    time_day TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX best_block_time_index ON best_block (time);
+CREATE INDEX best_block_name_time_index ON best_block (name, time);
@@ -0,0 +1,4 @@
+DROP INDEX time_5min_report_view_materialized_unique_index;
+DROP INDEX time_5min_avg_report_view_materialized_unique_index;
+DROP INDEX time_5min_extension_report_view_materialized_unique_index;
+DROP INDEX time_5min_peer_report_view_materialized_unique_index;
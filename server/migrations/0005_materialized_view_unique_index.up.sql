@@ -0,0 +1,9 @@
+-- REFRESH MATERIALIZED VIEW CONCURRENTLY requires a unique index on each view.
+CREATE UNIQUE INDEX time_5min_report_view_materialized_unique_index
+    ON time_5min_report_view_materialized (name, time_5min);
+CREATE UNIQUE INDEX time_5min_avg_report_view_materialized_unique_index
+    ON time_5min_avg_report_view_materialized (name, time_5min);
+CREATE UNIQUE INDEX time_5min_extension_report_view_materialized_unique_index
+    ON time_5min_extension_report_view_materialized (name, time_5min, extension);
+CREATE UNIQUE INDEX time_5min_peer_report_view_materialized_unique_index
+    ON time_5min_peer_report_view_materialized (name, time_5min, target_ip);
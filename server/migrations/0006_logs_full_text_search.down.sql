@@ -0,0 +1,2 @@
+DROP INDEX logs_message_tsv_index;
+ALTER TABLE logs DROP COLUMN message_tsv;
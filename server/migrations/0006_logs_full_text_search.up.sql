@@ -0,0 +1,8 @@
+-- Generated columns require Postgres 12 or later.
+-- This migration runs without a transaction, so that the index is built CONCURRENTLY while the logs are written.
+-- Adding the column still rewrites the logs table, and the logs can't be written until it finishes.
+-- Every statement can run again after a failure, which may leave an invalid index.
+ALTER TABLE logs ADD COLUMN IF NOT EXISTS message_tsv tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', message)) STORED;
+DROP INDEX CONCURRENTLY IF EXISTS logs_message_tsv_index;
+CREATE INDEX CONCURRENTLY logs_message_tsv_index ON logs USING GIN (message_tsv);
@@ -0,0 +1,13 @@
+CREATE TABLE users (
+    name VARCHAR PRIMARY KEY,
+    password_hash VARCHAR NOT NULL,
+    role VARCHAR NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+CREATE TABLE sessions (
+    token VARCHAR PRIMARY KEY,
+    user_name VARCHAR NOT NULL REFERENCES users (name) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+CREATE INDEX sessions_expires_at_index ON sessions (expires_at);
@@ -0,0 +1,12 @@
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    time TIMESTAMP WITH TIME ZONE NOT NULL,
+    user_name VARCHAR,
+    address VARCHAR,
+    method VARCHAR NOT NULL,
+    params JSONB NOT NULL,
+    result JSONB,
+    error VARCHAR
+);
+CREATE INDEX audit_log_time_index ON audit_log (time);
+CREATE INDEX audit_log_user_name_time_index ON audit_log (user_name, time);
@@ -0,0 +1,9 @@
+CREATE TABLE rollouts (
+    id BIGSERIAL PRIMARY KEY,
+    user_name VARCHAR,
+    status VARCHAR NOT NULL,
+    request JSONB NOT NULL,
+    nodes JSONB NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE node_tags (
+    name VARCHAR NOT NULL,
+    tag VARCHAR NOT NULL,
+    PRIMARY KEY (name, tag)
+);
+CREATE INDEX node_tags_tag_index ON node_tags (tag);
@@ -0,0 +1,22 @@
+CREATE TABLE jobs (
+    id BIGSERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL,
+    user_name VARCHAR,
+    nodes JSONB NOT NULL,
+    action JSONB NOT NULL,
+    schedule JSONB NOT NULL,
+    enabled BOOLEAN NOT NULL,
+    next_run_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE job_runs (
+    id BIGSERIAL PRIMARY KEY,
+    job_id BIGINT NOT NULL,
+    job_name VARCHAR NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    finished_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    results JSONB NOT NULL,
+    error VARCHAR
+);
+CREATE INDEX job_runs_job_id_index ON job_runs (job_id, started_at);
@@ -0,0 +1,2 @@
+DROP INDEX node_status_history_time_index;
+DROP INDEX peer_count_time_only_index;
@@ -0,0 +1,4 @@
+-- The retention job finds the old rows by time without a name. The other pruned tables already have these indexes.
+-- CREATE INDEX blocks the writes to the table until it finishes, so apply this while the server is stopped.
+CREATE INDEX peer_count_time_only_index ON peer_count (time);
+CREATE INDEX node_status_history_time_index ON node_status_history (time);
@@ -0,0 +1,9 @@
+CREATE TABLE materialized_view_refreshes (
+    view VARCHAR PRIMARY KEY,
+    last_run_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    last_duration_ms BIGINT NOT NULL,
+    last_success_at TIMESTAMP WITH TIME ZONE,
+    last_error VARCHAR,
+    consecutive_failures INTEGER NOT NULL,
+    total_failures BIGINT NOT NULL
+);
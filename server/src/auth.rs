@@ -0,0 +1,161 @@
+use lazy_static::lazy_static;
+use parking_lot::Mutex;
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+const TOKEN_LENGTH: usize = 40;
+/// The failed logins allowed from an address before it is locked out
+const MAX_FAILED_LOGINS: u32 = 5;
+const LOGIN_LOCKOUT: Duration = Duration::from_secs(5 * 60);
+
+lazy_static! {
+    /// Verified for the unknown users, so that the response time doesn't tell whether a user exists.
+    static ref DUMMY_PASSWORD_HASH: String = hash_password("dummy password").expect("Hashing a password never fails");
+}
+
+/// The roles are ordered by their permissions. A role can call every RPC that a lower role can call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    Viewer,
+    Operator,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "viewer"),
+            Role::Operator => write!(f, "operator"),
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "viewer" => Ok(Role::Viewer),
+            "operator" => Ok(Role::Operator),
+            _ => Err(format!("Unknown role {}", s)),
+        }
+    }
+}
+
+pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
+    bcrypt::hash(password, bcrypt::DEFAULT_COST)
+}
+
+pub fn verify_password(password: &str, password_hash: &str) -> bool {
+    bcrypt::verify(password, password_hash).unwrap_or(false)
+}
+
+/// Returns false after taking as long as `verify_password` does.
+pub fn verify_password_of_unknown_user(password: &str) -> bool {
+    verify_password(password, &DUMMY_PASSWORD_HASH);
+    false
+}
+
+/// Counts the failed logins of each address. An address that failed `MAX_FAILED_LOGINS` times
+/// cannot log in until `LOGIN_LOCKOUT` passes from its last failure.
+#[derive(Default)]
+pub struct LoginLimiter {
+    failures: Mutex<HashMap<Option<IpAddr>, (u32, Instant)>>,
+}
+
+impl LoginLimiter {
+    pub fn is_locked_out(&self, address: Option<IpAddr>, now: Instant) -> bool {
+        match self.failures.lock().get(&address) {
+            Some((count, last_failure)) => *count >= MAX_FAILED_LOGINS && now - *last_failure < LOGIN_LOCKOUT,
+            None => false,
+        }
+    }
+
+    pub fn fail(&self, address: Option<IpAddr>, now: Instant) {
+        let mut failures = self.failures.lock();
+        failures.retain(|_, (_, last_failure)| now - *last_failure < LOGIN_LOCKOUT);
+        let failure = failures.entry(address).or_insert((0, now));
+        failure.0 += 1;
+        failure.1 = now;
+    }
+
+    pub fn succeed(&self, address: Option<IpAddr>) {
+        self.failures.lock().remove(&address);
+    }
+}
+
+/// Compares the secrets in a time independent of the position of the first different byte.
+pub fn secure_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+pub fn generate_token() -> String {
+    thread_rng().sample_iter(&Alphanumeric).take(TOKEN_LENGTH).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operator_has_viewer_permissions() {
+        assert!(Role::Operator >= Role::Viewer);
+        assert!(Role::Viewer < Role::Operator);
+    }
+
+    #[test]
+    fn role_round_trip() {
+        for role in &[Role::Viewer, Role::Operator] {
+            assert_eq!(Ok(*role), role.to_string().parse());
+        }
+        assert!("admin".parse::<Role>().is_err());
+    }
+
+    #[test]
+    fn compare_secrets() {
+        assert!(secure_eq("secret", "secret"));
+        assert!(!secure_eq("secret", "secreT"));
+        assert!(!secure_eq("secret", "secret2"));
+    }
+
+    #[test]
+    fn lock_out_after_failed_logins() {
+        let limiter = LoginLimiter::default();
+        let address = Some("127.0.0.1".parse().unwrap());
+        let other = Some("127.0.0.2".parse().unwrap());
+        let now = Instant::now();
+        for _ in 0..MAX_FAILED_LOGINS {
+            assert!(!limiter.is_locked_out(address, now));
+            limiter.fail(address, now);
+        }
+        assert!(limiter.is_locked_out(address, now));
+        assert!(!limiter.is_locked_out(other, now));
+        assert!(!limiter.is_locked_out(address, now + LOGIN_LOCKOUT));
+    }
+
+    #[test]
+    fn successful_login_resets_failures() {
+        let limiter = LoginLimiter::default();
+        let address = Some("127.0.0.1".parse().unwrap());
+        let now = Instant::now();
+        for _ in 0..MAX_FAILED_LOGINS - 1 {
+            limiter.fail(address, now);
+        }
+        limiter.succeed(address);
+        limiter.fail(address, now);
+        assert!(!limiter.is_locked_out(address, now));
+    }
+
+    #[test]
+    fn verify_hashed_password() {
+        let password_hash = bcrypt::hash("secret", 4).unwrap();
+        assert!(verify_password("secret", &password_hash));
+        assert!(!verify_password("wrong", &password_hash));
+    }
+}
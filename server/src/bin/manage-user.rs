@@ -0,0 +1,102 @@
+use codechain_dashboard_server::auth::{self, Role};
+use codechain_dashboard_server::config::Config;
+use codechain_dashboard_server::{cerror, cinfo, logger_init};
+use postgres::{Connection, TlsMode};
+use std::io::{self, BufRead};
+use std::process;
+
+const USAGE: &str = "Usage: manage-user [list | add NAME ROLE | password NAME | role NAME ROLE | remove NAME]\n\
+                     ROLE is viewer or operator. The password is read from USER_PASSWORD or the standard input.";
+
+fn main() {
+    logger_init().expect("Logger should be initialized");
+
+    let config = Config::load().unwrap_or_else(|err| panic!("{}", err));
+    let conn = Connection::connect(config.db.url(), TlsMode::None).unwrap();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        [] | ["list"] => list(&conn),
+        ["add", name, role] => {
+            let role = parse_role(role);
+            let password_hash = hash_password(&read_password());
+            conn.execute("INSERT INTO users (name, password_hash, role) VALUES ($1, $2, $3)", &[
+                name,
+                &password_hash,
+                &role.to_string(),
+            ])
+            .map(|_| cinfo!("User {} is added as {}", name, role))
+        }
+        ["password", name] => {
+            let password_hash = hash_password(&read_password());
+            update(&conn, name, "UPDATE users SET password_hash=$2 WHERE name=$1", &password_hash)
+        }
+        ["role", name, role] => {
+            let role = parse_role(role);
+            update(&conn, name, "UPDATE users SET role=$2 WHERE name=$1", &role.to_string())
+        }
+        ["remove", name] => conn.execute("DELETE FROM users WHERE name=$1", &[name]).map(|count| {
+            if count == 0 {
+                cerror!("There is no user {}", name);
+                process::exit(1);
+            }
+            cinfo!("User {} is removed", name)
+        }),
+        _ => {
+            cerror!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+    if let Err(err) = result {
+        cerror!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn list(conn: &Connection) -> postgres::Result<()> {
+    for row in &conn.query("SELECT name, role FROM users ORDER BY name", &[])? {
+        let name: String = row.get("name");
+        let role: String = row.get("role");
+        cinfo!("{} {}", name, role);
+    }
+    Ok(())
+}
+
+fn update(conn: &Connection, name: &str, sql: &str, value: &str) -> postgres::Result<()> {
+    if conn.execute(sql, &[&name, &value])? == 0 {
+        cerror!("There is no user {}", name);
+        process::exit(1);
+    }
+    // Sign out the sessions. The connections using them are rejected from their next request.
+    conn.execute("DELETE FROM sessions WHERE user_name=$1", &[&name])?;
+    cinfo!("User {} is updated", name);
+    Ok(())
+}
+
+fn parse_role(role: &str) -> Role {
+    role.parse().unwrap_or_else(|err| {
+        cerror!("{}\n{}", err, USAGE);
+        process::exit(1);
+    })
+}
+
+fn read_password() -> String {
+    let password = std::env::var("USER_PASSWORD").unwrap_or_else(|_| {
+        let mut password = String::new();
+        io::stdin().lock().read_line(&mut password).expect("Read the password from the standard input");
+        password.trim_end_matches(|c| c == '\n' || c == '\r').to_string()
+    });
+    if password.is_empty() {
+        cerror!("The password is empty");
+        process::exit(1);
+    }
+    password
+}
+
+fn hash_password(password: &str) -> String {
+    auth::hash_password(password).unwrap_or_else(|err| {
+        cerror!("Cannot hash the password: {}", err);
+        process::exit(1);
+    })
+}
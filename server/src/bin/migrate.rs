@@ -0,0 +1,43 @@
+use codechain_dashboard_server::config::Config;
+use codechain_dashboard_server::migration;
+use codechain_dashboard_server::{cerror, cinfo, logger_init};
+use postgres::{Connection, TlsMode};
+use std::process;
+
+const USAGE: &str = "Usage: migrate [status | up [VERSION] | down VERSION]";
+
+fn main() {
+    logger_init().expect("Logger should be initialized");
+
+    let config = Config::load().unwrap_or_else(|err| panic!("{}", err));
+    let conn = Connection::connect(config.db.url(), TlsMode::None).unwrap();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        [] | ["status"] => {
+            let current = migration::current_version(&conn).unwrap();
+            cinfo!("Current version: {}, latest version: {}", current, migration::latest_version());
+            Ok(())
+        }
+        ["up"] => migration::up(&conn, migration::latest_version()),
+        ["up", version] => migration::up(&conn, parse_version(version)),
+        ["down", version] => migration::down(&conn, parse_version(version)),
+        _ => {
+            cerror!("{}", USAGE);
+            process::exit(1);
+        }
+    };
+    if let Err(err) = result {
+        cerror!("{}", err);
+        process::exit(1);
+    }
+    cinfo!("Schema version is {}", migration::current_version(&conn).unwrap());
+}
+
+fn parse_version(version: &str) -> i32 {
+    version.parse().unwrap_or_else(|_| {
+        cerror!("Invalid version {}\n{}", version, USAGE);
+        process::exit(1);
+    })
+}
@@ -1,14 +1,12 @@
+use codechain_dashboard_server::config::Config;
 use codechain_dashboard_server::logger_init;
 use postgres::{Connection, TlsMode};
 
 fn main() {
     logger_init().expect("Logger should be initialized");
 
-    // FIXME: move to configuration file
-    let user = "codechain-dashboard-server";
-    let password = "preempt-entreat-bell-chanson";
-    let conn_uri = format!("postgres://{}:{}@localhost", user, password);
-    let conn = Connection::connect(conn_uri, TlsMode::None).unwrap();
+    let config = Config::load().unwrap_or_else(|err| panic!("{}", err));
+    let conn = Connection::connect(config.db.url(), TlsMode::None).unwrap();
 
     conn.execute("REFRESH MATERIALIZED VIEW time_5min_report_view_materialized", &[]).unwrap();
     conn.execute("REFRESH MATERIALIZED VIEW time_5min_avg_report_view_materialized", &[]).unwrap();
@@ -0,0 +1,222 @@
+use super::client::{UpdateResult, UPDATE_INTERVAL};
+use crate::config::{AlertMetric, AlertRule, Severity};
+
+pub struct Alert {
+    pub severity: Severity,
+    pub message: String,
+}
+
+struct RuleState {
+    rule: AlertRule,
+    required_count: usize,
+    count: usize,
+}
+
+/// Keeps how long each alert rule has been satisfied for a client.
+/// An alert is sent once when a rule has been satisfied for its duration, and again only after the rule stops being
+/// satisfied.
+#[derive(Default)]
+pub struct AlertEvaluator {
+    rules: Vec<RuleState>,
+    highest_best_block_number: u64,
+}
+
+impl AlertEvaluator {
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        let interval = UPDATE_INTERVAL.as_secs();
+        self.rules = rules
+            .into_iter()
+            .map(|rule| RuleState {
+                required_count: std::cmp::max(1, (rule.duration + interval - 1) / interval) as usize,
+                rule,
+                count: 0,
+            })
+            .collect();
+    }
+
+    pub fn evaluate(&mut self, node_name: &str, update_result: &UpdateResult) -> Vec<Alert> {
+        let best_block_increase = update_result
+            .best_block_number
+            .map(|best_block_number| best_block_number as f64 - self.highest_best_block_number as f64);
+
+        let mut alerts = Vec::new();
+        for state in &mut self.rules {
+            let values = match metric_values(&state.rule, update_result, best_block_increase) {
+                Some(values) => values,
+                None => continue,
+            };
+            let matched: Vec<f64> = values
+                .into_iter()
+                .filter(|value| state.rule.comparator.compare(*value, state.rule.threshold))
+                .collect();
+            if matched.is_empty() {
+                state.count = 0;
+                continue
+            }
+
+            state.count += 1;
+            if state.count == state.required_count {
+                alerts.push(Alert {
+                    severity: state.rule.severity,
+                    message: alert_message(node_name, &state.rule, state.required_count, &matched),
+                });
+            }
+        }
+
+        if let Some(best_block_number) = update_result.best_block_number {
+            if best_block_number > self.highest_best_block_number {
+                self.highest_best_block_number = best_block_number;
+            }
+        }
+        alerts
+    }
+}
+
+fn metric_values(rule: &AlertRule, update_result: &UpdateResult, best_block_increase: Option<f64>) -> Option<Vec<f64>> {
+    match rule.metric {
+        AlertMetric::NumberOfPeers => Some(vec![update_result.number_of_peers as f64]),
+        AlertMetric::BestBlockIncrease => best_block_increase.map(|increase| vec![increase]),
+        AlertMetric::DiskAvailable => {
+            let disk_usages = match (&update_result.disk_usages, update_result.disk_usage) {
+                (Some(disk_usages), _) => disk_usages.clone(),
+                (None, Some(disk_usage)) => vec![disk_usage],
+                (None, None) => return None,
+            };
+            Some(
+                disk_usages
+                    .iter()
+                    .filter(|usage| usage.total >= rule.min_total as i64)
+                    .map(|usage| usage.available as f64)
+                    .collect(),
+            )
+        }
+        AlertMetric::MemoryAvailable => {
+            let memory_usage = update_result.memory_usage;
+            if memory_usage.total == 0 {
+                None
+            } else {
+                Some(vec![memory_usage.available as f64])
+            }
+        }
+    }
+}
+
+fn alert_message(node_name: &str, rule: &AlertRule, required_count: usize, values: &[f64]) -> String {
+    let format_value = |value: f64| match rule.metric {
+        AlertMetric::NumberOfPeers | AlertMetric::BestBlockIncrease => format!("{}", value),
+        AlertMetric::DiskAvailable | AlertMetric::MemoryAvailable => format!("{} MB", (value / 1_000_000.0) as i64),
+    };
+    let description = match rule.metric {
+        AlertMetric::NumberOfPeers => "the number of peers",
+        AlertMetric::BestBlockIncrease => "the best block number increase",
+        AlertMetric::DiskAvailable => "the free disk space",
+        AlertMetric::MemoryAvailable => "the free memory",
+    };
+    let values: Vec<String> = values.iter().map(|value| format_value(*value)).collect();
+    let duration = if required_count > 1 {
+        format!(" for {} seconds", required_count as u64 * UPDATE_INTERVAL.as_secs())
+    } else {
+        String::new()
+    };
+    format!(
+        "{}: {} is {}{}. ({}: {} {})",
+        node_name,
+        description,
+        values.join(", "),
+        duration,
+        rule.name,
+        rule.comparator,
+        format_value(rule.threshold)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_rpc_types::HardwareUsage;
+    use crate::config::Comparator;
+
+    fn update_result(number_of_peers: usize, best_block_number: u64) -> UpdateResult {
+        UpdateResult {
+            network_id: "tc".to_string(),
+            number_of_peers,
+            best_block_number: Some(best_block_number),
+            disk_usage: None,
+            disk_usages: Some(vec![
+                HardwareUsage {
+                    total: 2_000_000_000,
+                    available: 1_000_000_000,
+                    percentage_used: 0.5,
+                },
+                HardwareUsage {
+                    total: 10_000_000_000,
+                    available: 5_000_000_000,
+                    percentage_used: 0.5,
+                },
+            ]),
+            memory_usage: HardwareUsage {
+                total: 0,
+                available: 0,
+                percentage_used: 0.0,
+            },
+        }
+    }
+
+    fn rule(metric: AlertMetric, comparator: Comparator, threshold: f64, duration: u64) -> AlertRule {
+        AlertRule {
+            name: "test".to_string(),
+            metric,
+            comparator,
+            threshold,
+            duration,
+            min_total: 0,
+            severity: Severity::Warn,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn alert_once_after_duration() {
+        let mut evaluator = AlertEvaluator::default();
+        evaluator.set_rules(vec![rule(AlertMetric::NumberOfPeers, Comparator::Lt, 5.0, 30)]);
+
+        assert!(evaluator.evaluate("node", &update_result(1, 1)).is_empty());
+        assert!(evaluator.evaluate("node", &update_result(1, 2)).is_empty());
+        assert_eq!(1, evaluator.evaluate("node", &update_result(1, 3)).len());
+        assert!(evaluator.evaluate("node", &update_result(1, 4)).is_empty());
+
+        assert!(evaluator.evaluate("node", &update_result(5, 5)).is_empty());
+        assert!(evaluator.evaluate("node", &update_result(1, 6)).is_empty());
+        assert!(evaluator.evaluate("node", &update_result(1, 7)).is_empty());
+        assert_eq!(1, evaluator.evaluate("node", &update_result(1, 8)).len());
+    }
+
+    #[test]
+    fn alert_when_block_is_not_increased() {
+        let mut evaluator = AlertEvaluator::default();
+        evaluator.set_rules(vec![rule(AlertMetric::BestBlockIncrease, Comparator::Le, 0.0, 20)]);
+
+        assert!(evaluator.evaluate("node", &update_result(5, 10)).is_empty());
+        assert!(evaluator.evaluate("node", &update_result(5, 10)).is_empty());
+        let alerts = evaluator.evaluate("node", &update_result(5, 10));
+        assert_eq!(1, alerts.len());
+        assert_eq!("node: the best block number increase is 0 for 20 seconds. (test: <= 0)", alerts[0].message);
+    }
+
+    #[test]
+    fn small_disks_are_ignored() {
+        let mut evaluator = AlertEvaluator::default();
+        evaluator.set_rules(vec![rule(AlertMetric::DiskAvailable, Comparator::Lt, 6_000_000_000.0, 0)]);
+        let alerts = evaluator.evaluate("node", &update_result(5, 1));
+        assert_eq!(1, alerts.len());
+        assert_eq!("node: the free disk space is 1000 MB, 5000 MB. (test: < 6000 MB)", alerts[0].message);
+
+        evaluator.set_rules(vec![AlertRule {
+            min_total: 3_000_000_000,
+            ..rule(AlertMetric::DiskAvailable, Comparator::Lt, 6_000_000_000.0, 0)
+        }]);
+        let alerts = evaluator.evaluate("node", &update_result(5, 2));
+        assert_eq!(1, alerts.len());
+        assert_eq!("node: the free disk space is 5000 MB. (test: < 6000 MB)", alerts[0].message);
+    }
+}
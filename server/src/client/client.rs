@@ -4,10 +4,12 @@ use super::super::common_rpc_types::{
 };
 use super::super::rpc::RPCResult;
 use super::super::{db, jsonrpc};
+use super::alert::AlertEvaluator;
 use super::codechain_rpc::CodeChainRPC;
 use super::service::{Message as ServiceMessage, ServiceSender};
 use super::types::{ClientGetInfoResponse, CodeChainCallRPCResponse};
 use crate::common_rpc_types::HardwareUsage;
+use crate::config::{AlertConfig, ClientConfig, Severity};
 use crate::noti::Noti;
 use jsonrpc_core::Output;
 use parking_lot::{RwLock, RwLockReadGuard};
@@ -21,6 +23,8 @@ use std::thread;
 use std::time::Duration;
 use ws::CloseCode as WSCloseCode;
 
+pub const UPDATE_INTERVAL: Duration = Duration::from_secs(10);
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
 pub enum State {
@@ -193,6 +197,11 @@ pub struct Client {
     db_service: db::ServiceSender,
     codechain_rpc: CodeChainRPC,
     noti: Arc<Noti>,
+    config: Arc<ClientConfig>,
+    alert_config: Arc<AlertConfig>,
+    alerts: AlertEvaluator,
+    /// The client must report this name if it is set.
+    authenticated_name: Option<NodeName>,
 }
 
 pub enum ClientCleanupReason {
@@ -204,12 +213,16 @@ pub enum ClientCleanupReason {
 }
 
 impl Client {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         id: i32,
         jsonrpc_context: jsonrpc::Context,
+        authenticated_name: Option<NodeName>,
         service_sender: ServiceSender,
         db_service: db::ServiceSender,
         noti: Arc<Noti>,
+        config: Arc<ClientConfig>,
+        alert_config: Arc<AlertConfig>,
     ) -> Self {
         let state = Arc::new(RwLock::new(State::new()));
         let sender = ClientSender::new(jsonrpc_context, Arc::clone(&state));
@@ -222,17 +235,26 @@ impl Client {
             db_service,
             codechain_rpc: CodeChainRPC::new(sender),
             noti,
+            config,
+            alert_config,
+            alerts: AlertEvaluator::default(),
+            authenticated_name,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn run_thread(
         id: i32,
         jsonrpc_context: jsonrpc::Context,
+        authenticated_name: Option<NodeName>,
         service_sender: ServiceSender,
         db_service: db::ServiceSender,
         noti: Arc<Noti>,
+        config: Arc<ClientConfig>,
+        alert_config: Arc<AlertConfig>,
     ) -> ClientSender {
-        let mut client = Self::new(id, jsonrpc_context, service_sender, db_service, noti);
+        let mut client =
+            Self::new(id, jsonrpc_context, authenticated_name, service_sender, db_service, noti, config, alert_config);
         let sender = client.sender.clone();
 
         thread::Builder::new()
@@ -270,30 +292,23 @@ impl Client {
         // if exist, run it.
         let name = self.state.read().name().expect("Updated").clone();
 
-        if let Ok(Some(extra)) = self.db_service.get_client_extra(name) {
-            match ::std::env::var("START_AT_CONNECT") {
-                Ok(_) => {
-                    if let Err(err) = self.sender.shell_start_codechain(ShellStartCodeChainRequest {
-                        env: extra.prev_env,
-                        args: extra.prev_args,
-                    }) {
-                        cerror!("Cannot start CodeChain {}", err);
-                    }
-                }
-                Err(_) => {
-                    cinfo!("Do not start CodeChain at connected");
+        if let Ok(Some(extra)) = self.db_service.get_client_extra(name.clone()) {
+            if self.config.start_at_connect {
+                if let Err(err) = self.sender.shell_start_codechain(ShellStartCodeChainRequest {
+                    env: extra.prev_env,
+                    args: extra.prev_args,
+                }) {
+                    cerror!("Cannot start CodeChain {}", err);
                 }
+            } else {
+                cinfo!("Do not start CodeChain at connected");
             }
         }
 
-        const REQUIRED_NUMBER_OF_PEERS: usize = 5usize;
-        let mut count_of_no_enough_connections = 0usize;
-        let mut previous_best_block_number = 0;
-        let mut count_of_no_block_update = 0usize;
-        let mut disk_usage_alert_sent = false;
-        let mut memory_usage_alert_sent = false;
+        let mut tags = None;
         loop {
             ctrace!("Client-{} update", self.id);
+            self.update_alert_rules(&name, &mut tags);
             let update_result = self.update()?;
             let node_name = match &*self.state.read() {
                 State::Stop {
@@ -306,105 +321,44 @@ impl Client {
                     ..
                 } => Some(name.clone()),
             };
-            // TODO: Remove the below magic numbers
-            if let Some(UpdateResult {
-                network_id,
-                number_of_peers,
-                best_block_number,
-                disk_usage,
-                disk_usages,
-                memory_usage,
-            }) = update_result
-            {
+            if let Some(update_result) = update_result {
                 let node_name = node_name.expect("Updated");
-                if number_of_peers < REQUIRED_NUMBER_OF_PEERS {
-                    count_of_no_enough_connections += 1;
-                } else {
-                    count_of_no_enough_connections = 0;
-                }
-                if count_of_no_enough_connections == 12 {
-                    self.noti.warn(
-                        &network_id,
-                        &format!(
-                            "{} failed to establish enough connections in two minutes. (current connection count/required connection count) = ({}/{})",
-                            node_name,
-                            number_of_peers,
-                            REQUIRED_NUMBER_OF_PEERS
-                        ),
-                    );
-                }
-
-                if let Some(best_block_number) = best_block_number {
-                    if best_block_number > previous_best_block_number {
-                        count_of_no_block_update = 0;
-                        previous_best_block_number = best_block_number;
-                    } else {
-                        count_of_no_block_update += 1;
-                    }
-
-                    if count_of_no_block_update == 3 {
-                        self.noti.warn(&network_id, &format!("{} no block update in 30 seconds.", node_name));
-                    }
-                }
-
-                const THREE_GB: i64 = 3_000_000_000;
-                if !disk_usage_alert_sent {
-                    if let Some(disk_usages) = disk_usages {
-                        let less_space_disks: Vec<&HardwareUsage> = disk_usages
-                            .iter()
-                            .filter(|usage| usage.total > THREE_GB && usage.available < THREE_GB)
-                            .collect();
-                        if !less_space_disks.is_empty() {
-                            let disk_spaces: String = less_space_disks
-                                .into_iter()
-                                .map(|usage| (usage.available / 1_000_000).to_string())
-                                .collect::<Vec<_>>()
-                                .join(", ");
-                            self.noti.error(
-                                &network_id,
-                                &format!("{} has only {} MB free disk space.", node_name, disk_spaces),
-                            );
-                            disk_usage_alert_sent = true;
-                        } else {
-                            disk_usage_alert_sent = false;
-                        }
-                    } else if let Some(disk_usage) = disk_usage {
-                        if disk_usage.total > THREE_GB && disk_usage.available < THREE_GB {
-                            self.noti.error(
-                                &network_id,
-                                &format!(
-                                    "{} has only {} MB free disk space.",
-                                    node_name,
-                                    disk_usage.available / 1_000_000
-                                ),
-                            );
-                            disk_usage_alert_sent = true;
-                        } else if THREE_GB < disk_usage.available {
-                            disk_usage_alert_sent = false;
-                        }
+                for alert in self.alerts.evaluate(&node_name, &update_result) {
+                    match alert.severity {
+                        Severity::Warn => self.noti.warn(&update_result.network_id, &alert.message),
+                        Severity::Error => self.noti.error(&update_result.network_id, &alert.message),
                     }
                 }
+            }
+            thread::sleep(UPDATE_INTERVAL);
+        }
+    }
 
-                const ONE_GB: i64 = 1_000_000_000;
-                let enable_memory_alarm = ::std::env::var("ENABLE_MEMORY_ALARM").is_ok();
-                if enable_memory_alarm && !memory_usage_alert_sent {
-                    if memory_usage.total != 0 && memory_usage.available < (ONE_GB / 4) {
-                        self.noti.warn(
-                            &network_id,
-                            &format!("{} has only {} MB free memory.", node_name, memory_usage.available / 1_000_000),
-                        );
-                        memory_usage_alert_sent = true;
-                    } else if (ONE_GB / 4) < memory_usage.available {
-                        memory_usage_alert_sent = false;
-                    }
+    /// Applies the alert rules of the tags when the tags of the node are changed by `node_setTags`.
+    fn update_alert_rules(&mut self, name: &str, current_tags: &mut Option<Vec<String>>) {
+        let tags = match self.db_service.get_tags_of_node(name.to_string()) {
+            Ok(tags) => tags,
+            Err(err) => {
+                cwarn!("Cannot get the tags of {}: {:?}", name, err);
+                if current_tags.is_some() {
+                    return
                 }
+                Vec::new()
             }
-            thread::sleep(Duration::new(10, 0));
+        };
+        if current_tags.as_ref() != Some(&tags) {
+            self.alerts.set_rules(self.alert_config.rules_for(name, &tags));
+            *current_tags = Some(tags);
         }
     }
 
     fn update(&mut self) -> Result<Option<UpdateResult>, String> {
         let info = self.sender.client_get_info().map_err(|err| format!("{}", err))?;
+        if let Some(authenticated_name) = &self.authenticated_name {
+            if authenticated_name != &info.name {
+                return Err(format!("The client authenticated as {} reports the name {}", authenticated_name, info.name))
+            }
+        }
 
         let mut state = self.state.write();
         let new_state = State::Normal {
@@ -477,6 +431,7 @@ impl Client {
         let disk_usage = hardware.disk_usage;
         let disk_usages = hardware.disk_usages.clone();
         let memory_usage = hardware.memory_usage;
+        let hardware_usage = hardware.clone();
         self.db_service.update_client_query_result(db::ClientQueryResult {
             name: info.name.clone(),
             status: info.status,
@@ -492,6 +447,10 @@ impl Client {
         *state = new_state;
 
         let now = chrono::Utc::now();
+        self.db_service.write_hardware_usage(info.name.clone(), hardware_usage, now);
+        if let Some(best_block_id) = best_block_id {
+            self.db_service.write_best_block(info.name.clone(), best_block_id.block_number, now);
+        }
         if let Some(network_usage) = network_usage {
             self.db_service.write_network_usage(info.name.clone(), network_usage, now);
             self.db_service.write_peer_count(
@@ -552,12 +511,7 @@ impl Client {
             ..
         } = &*state
         {
-            self.db_service.update_client_query_result(db::ClientQueryResult {
-                name: name.clone(),
-                status: NodeStatus::Error,
-                address: *address,
-                ..Default::default()
-            });
+            self.db_service.disconnect_client(name.clone(), *address);
         }
 
         let ws_close_result = self.sender.jsonrpc_context.ws_sender.close_with_reason(
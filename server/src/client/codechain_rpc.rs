@@ -4,6 +4,7 @@ use super::super::common_rpc_types::{
 use super::client::{ClientSender, SendClientRPC};
 use super::types::ChainGetBestBlockIdResponse;
 use jsonrpc_core::types::{Failure, Output, Success};
+use primitives::H256;
 use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 use std::net::SocketAddr;
@@ -37,6 +38,10 @@ impl CodeChainRPC {
         }))
     }
 
+    pub fn get_block_hash(&self, status: NodeStatus, block_number: i64) -> Result<Option<H256>, String> {
+        self.call_rpc(status, "chain_getBlockHash", vec![Value::from(block_number)])
+    }
+
     pub fn version(&self, status: NodeStatus) -> Result<Option<String>, String> {
         self.call_rpc(status, "version", Vec::new())
     }
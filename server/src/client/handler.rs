@@ -1,32 +1,85 @@
-use super::super::{client, jsonrpc};
-use ws::{self, CloseCode, Error as WSError, Handler, Handshake, Result, Sender as WSSender};
+use super::super::{client, jsonrpc, tls};
+use crate::config::ClientConfig;
+use openssl::ssl::{SslAcceptor, SslStream};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use ws::util::TcpStream;
+use ws::{self, CloseCode, Error as WSError, ErrorKind, Handler, Handshake, Result, Sender as WSSender};
+
+/// The handshake headers that an agent authenticates with.
+pub const NODE_NAME_HEADER: &str = "X-Node-Name";
+pub const NODE_KEY_HEADER: &str = "X-Node-Key";
+
+#[derive(Debug)]
+struct AuthorizationError {}
+
+impl Error for AuthorizationError {}
+
+impl fmt::Display for AuthorizationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Authorization error")
+    }
+}
 
 pub struct WebSocketHandler {
     pub out: WSSender,
     pub count: usize,
     pub client_service: client::ServiceSender,
     pub jsonrpc_context: jsonrpc::Context,
+    pub config: Arc<ClientConfig>,
+    pub ssl: Option<Arc<SslAcceptor>>,
 }
 
 impl WebSocketHandler {
-    pub fn new(out: WSSender, client_service: client::ServiceSender) -> Self {
+    pub fn new(
+        out: WSSender,
+        client_service: client::ServiceSender,
+        config: Arc<ClientConfig>,
+        ssl: Option<Arc<SslAcceptor>>,
+    ) -> Self {
         let jsonrpc_context = jsonrpc::Context::new(out.clone());
-        client_service
-            .send(client::Message::InitializeClient(jsonrpc_context.clone()))
-            .expect("Should success send InitializeClient to service");
         Self {
             out,
             count: 0,
             client_service,
             jsonrpc_context,
+            config,
+            ssl,
         }
     }
+
+    /// Returns the node name that the agent is authenticated as.
+    fn authenticate(&self, handshake: &Handshake) -> Result<Option<String>> {
+        let header = |name| {
+            handshake.request.header(name).and_then(|value| String::from_utf8(value.clone()).ok()).unwrap_or_default()
+        };
+        let name = header(NODE_NAME_HEADER);
+        if !self.config.requires_auth() {
+            return Ok(Some(name).filter(|name| !name.is_empty()))
+        }
+
+        if name.is_empty() || !self.config.authenticate(&name, &header(NODE_KEY_HEADER)) {
+            cwarn!("An agent from {:?} failed to authenticate as {:?}", handshake.peer_addr, name);
+            return Err(WSError::new(ErrorKind::Custom(Box::new(AuthorizationError {})), "Authorization Error"))
+        }
+        Ok(Some(name))
+    }
 }
 
 impl Handler for WebSocketHandler {
-    fn on_open(&mut self, _: Handshake) -> Result<()> {
+    fn upgrade_ssl_server(&mut self, sock: TcpStream) -> Result<SslStream<TcpStream>> {
+        tls::upgrade(self.ssl.as_deref(), sock)
+    }
+
+    fn on_open(&mut self, handshake: Handshake) -> Result<()> {
         // We have a new connection, so we increment the connection counter
         self.count += 1;
+
+        let name = self.authenticate(&handshake)?;
+        self.client_service
+            .send(client::Message::InitializeClient(self.jsonrpc_context.clone(), name))
+            .expect("Should success send InitializeClient to service");
         Ok(())
     }
 
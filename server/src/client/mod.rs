@@ -1,3 +1,4 @@
+mod alert;
 #[cfg_attr(feature = "cargo-clippy", allow(clippy::module_inception))]
 pub mod client;
 mod codechain_rpc;
@@ -6,5 +7,6 @@ pub mod service;
 mod types;
 
 pub use self::client::{SendClientRPC, State};
+pub use self::codechain_rpc::CodeChainRPC;
 pub use self::handler::WebSocketHandler;
 pub use self::service::{Message, Service, ServiceSender};
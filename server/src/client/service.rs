@@ -1,5 +1,7 @@
+use super::super::common_rpc_types::NodeName;
 use super::super::{db, jsonrpc};
 use super::client::{Client, ClientSender, State as ClientState};
+use crate::config::{AlertConfig, ClientConfig};
 use crate::noti::Noti;
 use parking_lot::RwLock;
 use std::sync::mpsc::{channel, SendError, Sender};
@@ -60,16 +62,24 @@ pub struct Service {
     next_id: i32,
     sender: ServiceSender,
     db_service: db::ServiceSender,
+    config: Arc<ClientConfig>,
+    alert_config: Arc<AlertConfig>,
 }
 
 pub enum Message {
-    InitializeClient(jsonrpc::Context),
+    /// The name is set when the agent is authenticated in the handshake.
+    InitializeClient(jsonrpc::Context, Option<NodeName>),
     AddClient(i32, ClientSender),
     RemoveClient(i32),
 }
 
 impl Service {
-    pub fn run_thread(db_service: db::ServiceSender, noti: Arc<Noti>) -> ServiceSender {
+    pub fn run_thread(
+        db_service: db::ServiceSender,
+        noti: Arc<Noti>,
+        config: ClientConfig,
+        alert_config: AlertConfig,
+    ) -> ServiceSender {
         let (sender, rx) = channel();
         let state = Default::default();
         let service_sender = ServiceSender {
@@ -77,15 +87,15 @@ impl Service {
             state: Arc::clone(&state),
         };
 
-        let mut service = Service::new(service_sender.clone(), state, db_service);
+        let mut service = Service::new(service_sender.clone(), state, db_service, config, alert_config);
 
         thread::Builder::new()
             .name("client service".to_string())
             .spawn(move || {
                 for message in rx {
                     match message {
-                        Message::InitializeClient(jsonrpc_context) => {
-                            service.create_client(jsonrpc_context, Arc::clone(&noti));
+                        Message::InitializeClient(jsonrpc_context, authenticated_name) => {
+                            service.create_client(jsonrpc_context, authenticated_name, Arc::clone(&noti));
                         }
                         Message::AddClient(id, client_sender) => {
                             service.add_client(id, client_sender);
@@ -101,19 +111,41 @@ impl Service {
         service_sender
     }
 
-    fn new(sender: ServiceSender, state: Arc<RwLock<State>>, db_service: db::ServiceSender) -> Self {
+    fn new(
+        sender: ServiceSender,
+        state: Arc<RwLock<State>>,
+        db_service: db::ServiceSender,
+        config: ClientConfig,
+        alert_config: AlertConfig,
+    ) -> Self {
         Service {
             state,
             next_id: 0_i32,
             sender,
             db_service,
+            config: Arc::new(config),
+            alert_config: Arc::new(alert_config),
         }
     }
 
-    fn create_client(&mut self, jsonrpc_context: jsonrpc::Context, noti: Arc<Noti>) {
+    fn create_client(
+        &mut self,
+        jsonrpc_context: jsonrpc::Context,
+        authenticated_name: Option<NodeName>,
+        noti: Arc<Noti>,
+    ) {
         let id = self.next_id;
         self.next_id += 1;
-        Client::run_thread(id, jsonrpc_context, self.sender.clone(), self.db_service.clone(), noti);
+        Client::run_thread(
+            id,
+            jsonrpc_context,
+            authenticated_name,
+            self.sender.clone(),
+            self.db_service.clone(),
+            noti,
+            Arc::clone(&self.config),
+            Arc::clone(&self.alert_config),
+        );
         cdebug!("Client {} initialization starts", id);
     }
 
@@ -2,7 +2,9 @@ use chrono::{DateTime, Utc};
 use primitives::H256;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::net::IpAddr;
+use std::str::FromStr;
 
 pub type NodeName = String;
 
@@ -22,6 +24,28 @@ impl Default for NodeStatus {
     }
 }
 
+impl fmt::Display for NodeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for NodeStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Starting" => Ok(NodeStatus::Starting),
+            "Run" => Ok(NodeStatus::Run),
+            "Stop" => Ok(NodeStatus::Stop),
+            "Updating" => Ok(NodeStatus::Updating),
+            "Error" => Ok(NodeStatus::Error),
+            "UFO" => Ok(NodeStatus::UFO),
+            _ => Err(format!("Invalid node status {}", s)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ShellStartCodeChainRequest {
@@ -33,6 +57,41 @@ pub type ShellUpdateCodeChainRequest = (ShellStartCodeChainRequest, UpdateCodeCh
 
 pub type Connection = (NodeName, NodeName);
 
+/// Selects the nodes of a batch RPC.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeSelector {
+    #[serde(default)]
+    pub node_names: Vec<NodeName>,
+    /// Selects the nodes having any of the tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Selects every node known to the server
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// The result of a node control for a node. `error` is None when the call succeeded.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeControlResult {
+    pub name: NodeName,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum NodeAction {
+    Start,
+    Stop,
+    Restart,
+    #[serde(rename_all = "camelCase")]
+    Update {
+        update: UpdateCodeChainRequest,
+    },
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Copy, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockId {
@@ -82,7 +141,7 @@ pub struct HardwareInfo {
     pub memory_usage: HardwareUsage,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct StructuredLog {
     pub level: String,
@@ -92,7 +151,7 @@ pub struct StructuredLog {
     pub thread_name: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type")]
 pub enum UpdateCodeChainRequest {
@@ -108,6 +167,155 @@ pub enum UpdateCodeChainRequest {
     },
 }
 
+impl UpdateCodeChainRequest {
+    /// Whether the node runs the requested version
+    pub fn is_applied(&self, version: &NodeVersion) -> bool {
+        match self {
+            UpdateCodeChainRequest::Git {
+                commit_hash,
+            } => &version.hash == commit_hash,
+            UpdateCodeChainRequest::Binary {
+                binary_checksum,
+                ..
+            } => &version.binary_checksum == binary_checksum,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRolloutRequest {
+    #[serde(default)]
+    pub node_names: Vec<NodeName>,
+    /// The nodes having any of the tags are added to `node_names` when the rollout starts.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub update: UpdateCodeChainRequest,
+    /// The number of nodes updated at once
+    pub batch_size: usize,
+    #[serde(default)]
+    pub on_error: RolloutErrorPolicy,
+}
+
+/// What to do when a node of a wave ends in Error. A paused rollout waits for `update_rolloutResume` or `update_rolloutAbort`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RolloutErrorPolicy {
+    Pause,
+    Abort,
+}
+
+impl Default for RolloutErrorPolicy {
+    fn default() -> Self {
+        RolloutErrorPolicy::Pause
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RolloutStatus {
+    Running,
+    Paused,
+    Aborted,
+    Completed,
+}
+
+impl fmt::Display for RolloutStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for RolloutStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Running" => Ok(RolloutStatus::Running),
+            "Paused" => Ok(RolloutStatus::Paused),
+            "Aborted" => Ok(RolloutStatus::Aborted),
+            "Completed" => Ok(RolloutStatus::Completed),
+            _ => Err(format!("Invalid rollout status {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RolloutNodeStatus {
+    Pending,
+    /// The update is requested, and the node has not run the new version and caught up yet.
+    Updating,
+    Done,
+    Error,
+    /// The rollout is aborted before updating the node.
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RolloutNode {
+    pub name: NodeName,
+    /// Starts from 0
+    pub wave: usize,
+    pub status: RolloutNodeStatus,
+    pub message: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rollout {
+    pub id: i64,
+    pub user_name: Option<String>,
+    pub status: RolloutStatus,
+    pub request: UpdateRolloutRequest,
+    pub nodes: Vec<RolloutNode>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type")]
+pub enum JobSchedule {
+    Once {
+        at: DateTime<Utc>,
+    },
+    /// A cron expression of five fields evaluated in UTC, such as "0 3 * * *"
+    Cron {
+        expression: String,
+    },
+}
+
+/// A node action registered to run on a schedule
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Job {
+    pub id: i64,
+    pub name: String,
+    pub user_name: Option<String>,
+    pub nodes: NodeSelector,
+    pub action: NodeAction,
+    pub schedule: JobSchedule,
+    pub enabled: bool,
+    /// None when the job will not run again
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRun {
+    pub id: i64,
+    pub job_id: i64,
+    pub job_name: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub results: Vec<NodeControlResult>,
+    /// The reason why the job could not run on the nodes
+    pub error: Option<String>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -123,6 +331,10 @@ pub struct GraphCommonArgs {
     pub from: DateTime<Utc>,
     pub to: DateTime<Utc>,
     pub period: GraphPeriod,
+    /// Selects the nodes having any of the tags in `graph_network_out_all_node` and `graph_network_out_all_node_avg`.
+    /// Empty selects every node.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -151,6 +363,78 @@ pub struct GraphNetworkOutNodePeerRow {
     pub value: f32,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPeerCountNodeRow {
+    pub time: DateTime<Utc>,
+    pub value: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPeerCountAllRow {
+    pub node_name: String,
+    pub time: DateTime<Utc>,
+    pub value: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphBlockLagAllRow {
+    pub node_name: String,
+    pub time: DateTime<Utc>,
+    pub height: i64,
+    pub lag: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphHardwareNodeRow {
+    pub metric: String,
+    pub time: DateTime<Utc>,
+    pub value: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphHardwareAllRow {
+    pub node_name: String,
+    pub metric: String,
+    pub time: DateTime<Utc>,
+    pub value: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableStatsRow {
+    pub table_name: String,
+    /// Bytes including the indexes
+    pub size: i64,
+    pub estimated_row_count: i64,
+    pub oldest: Option<DateTime<Utc>>,
+}
+
+/// The latest refresh of a materialized view by the server.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterializedViewRefreshRow {
+    pub view: String,
+    pub last_run_at: DateTime<Utc>,
+    pub last_duration_ms: i64,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// The error of the latest refresh, if it failed
+    pub last_error: Option<String>,
+    pub consecutive_failures: i32,
+    pub total_failures: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatusHistoryRow {
+    pub status: NodeStatus,
+    pub time: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,4 +454,18 @@ mod tests {
         let period = GraphPeriod::Hour;
         assert_eq!("\"hour\"", &serde_json::to_string(&period).unwrap());
     }
+
+    #[test]
+    fn node_status_from_display() {
+        for status in &[
+            NodeStatus::Starting,
+            NodeStatus::Run,
+            NodeStatus::Stop,
+            NodeStatus::Updating,
+            NodeStatus::Error,
+            NodeStatus::UFO,
+        ] {
+            assert_eq!(Ok(*status), status.to_string().parse());
+        }
+    }
 }
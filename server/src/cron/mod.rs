@@ -1 +1,2 @@
-pub mod remove_network_usage;
+pub mod refresh_materialized_view;
+pub mod retention;
@@ -0,0 +1,57 @@
+use crate::config::{CronConfig, DBConfig};
+use crate::db::queries::materialized_view;
+use r2d2_postgres::PostgresConnectionManager;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub fn run(db_config: &DBConfig, cron_config: &CronConfig) {
+    if cron_config.refresh_materialized_view_interval == 0 {
+        cinfo!("The materialized views are not refreshed by the server");
+        return
+    }
+
+    let manager = PostgresConnectionManager::new(db_config.url(), r2d2_postgres::TlsMode::None)
+        .expect("Create connection manager");
+    let pool = r2d2::Pool::new(manager).expect("Create connection pool");
+
+    let interval = Duration::from_secs(cron_config.refresh_materialized_view_interval);
+
+    thread::Builder::new()
+        .name("cron-refresh-materialized-view".to_string())
+        .spawn(move || loop {
+            match pool.get() {
+                Ok(connection) => {
+                    for view in materialized_view::MATERIALIZED_VIEWS {
+                        let run_at = chrono::Utc::now();
+                        let started = Instant::now();
+                        let result = materialized_view::refresh_concurrently(&connection, view);
+                        let duration_ms = started.elapsed().as_millis() as i64;
+                        let error = match result {
+                            Ok(()) => {
+                                cinfo!("Refreshed {} in {} ms", view, duration_ms);
+                                None
+                            }
+                            Err(err) => {
+                                cwarn!("Fail to refresh {} after {} ms: {:?}", view, duration_ms, err);
+                                Some(err.to_string())
+                            }
+                        };
+                        let recorded = materialized_view::record_refresh(
+                            &connection,
+                            view,
+                            run_at,
+                            duration_ms,
+                            error.as_ref().map(String::as_str),
+                        );
+                        if let Err(err) = recorded {
+                            cwarn!("Fail to record the refresh of {}: {:?}", view, err);
+                        }
+                    }
+                }
+                Err(err) => cwarn!("refresh_materialized_view: {:?}", err),
+            }
+
+            thread::sleep(interval);
+        })
+        .expect("Should success running cron-refresh-materialized-view");
+}
@@ -0,0 +1,84 @@
+use crate::config::{DBConfig, RetentionConfig};
+use crate::db::queries::retention;
+use crate::db::DBConnection;
+use r2d2_postgres::PostgresConnectionManager;
+use std::thread;
+use std::time::Duration;
+
+/// Gives the other connections a chance to use the tables between the batches.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+pub fn run(db_config: &DBConfig, retention_config: &RetentionConfig) {
+    let manager = PostgresConnectionManager::new(db_config.url(), r2d2_postgres::TlsMode::None)
+        .expect("Create connection manager");
+    let pool = r2d2::Pool::new(manager).expect("Create connection pool");
+
+    let config = retention_config.clone();
+    let interval = Duration::from_secs(config.interval);
+
+    thread::Builder::new()
+        .name("cron-retention".to_string())
+        .spawn(move || loop {
+            match pool.get() {
+                Ok(connection) => remove_expired_rows(&connection, &config),
+                Err(err) => cwarn!("retention: {:?}", err),
+            }
+
+            thread::sleep(interval);
+        })
+        .expect("Should success running cron-retention");
+}
+
+fn remove_expired_rows(conn: &DBConnection, config: &RetentionConfig) {
+    let now = chrono::Utc::now();
+    let batch_size = config.batch_size;
+
+    for (table, days) in config.time_series_tables() {
+        if days == 0 {
+            continue
+        }
+        let time = now - time::Duration::days(days as i64);
+        delete_in_batches(table, batch_size, || retention::delete_older_rows(conn, table, time, batch_size));
+    }
+
+    let mut levels = Vec::new();
+    for (level, days) in &config.logs.levels {
+        let level = level.to_uppercase();
+        levels.push(level.clone());
+        if *days == 0 {
+            continue
+        }
+        let time = now - time::Duration::days(*days as i64);
+        delete_in_batches("logs", batch_size, || retention::delete_older_logs_of_level(conn, &level, time, batch_size));
+    }
+    if config.logs.default != 0 {
+        let time = now - time::Duration::days(config.logs.default as i64);
+        delete_in_batches("logs", batch_size, || {
+            retention::delete_older_logs_except_levels(conn, &levels, time, batch_size)
+        });
+    }
+}
+
+fn delete_in_batches<F>(table: &str, batch_size: i64, mut delete: F)
+where
+    F: FnMut() -> postgres::Result<u64>, {
+    let mut total = 0;
+    loop {
+        match delete() {
+            Ok(deleted) => {
+                total += deleted;
+                if (deleted as i64) < batch_size {
+                    break
+                }
+            }
+            Err(err) => {
+                cwarn!("Fail to remove expired rows of {}: {:?}", table, err);
+                break
+            }
+        }
+        thread::sleep(BATCH_INTERVAL);
+    }
+    if total > 0 {
+        cinfo!("Removed {} expired rows of {}", total, table);
+    }
+}
@@ -8,9 +8,8 @@ pub fn start(
     noti: Arc<Noti>,
     db_service: DBServiceSender,
     client_service: ClientServiceSender,
+    network_id: String,
 ) -> thread::JoinHandle<()> {
-    let network_id = std::env::var("NETWORK_ID").expect("NETWORK_ID environment variable is needed");
-
     thread::Builder::new()
         .name("daily reporter".to_string())
         .spawn(move || {
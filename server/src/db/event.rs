@@ -1,4 +1,4 @@
-use super::super::common_rpc_types::NodeName;
+use super::super::common_rpc_types::{NodeName, StructuredLog};
 use super::types::{ClientExtra, ClientQueryResult};
 
 pub enum Event {
@@ -15,6 +15,15 @@ pub enum Event {
         before: Option<ClientExtra>,
         after: ClientExtra,
     },
+    LogsWritten {
+        name: NodeName,
+        tags: Vec<String>,
+        logs: Vec<StructuredLog>,
+    },
+    NodeTagsUpdated {
+        name: NodeName,
+        tags: Vec<String>,
+    },
 }
 
 pub trait EventSubscriber: Send {
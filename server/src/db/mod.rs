@@ -5,4 +5,7 @@ mod types;
 
 pub use self::event::{Event, EventSubscriber};
 pub use self::service::{Service, ServiceNewArg, ServiceSender};
-pub use self::types::{ClientExtra, ClientQueryResult, Error, Log, LogQueryParams};
+pub use self::types::{
+    AuditLog, AuditQueryParams, ClientExtra, ClientQueryResult, DBConnection, Error, Log, LogFilter, LogHistogramRow,
+    LogQueryParams, Session, User,
+};
@@ -0,0 +1,91 @@
+use super::super::types::{AuditLog, AuditQueryParams, DBConnection, OrderBy};
+use super::logs::{build_where_clause, Parameters};
+use postgres::types::ToSql;
+use std::borrow::Borrow;
+use std::rc::Rc;
+
+pub fn insert(conn: &DBConnection, audit_log: &AuditLog) -> postgres::Result<()> {
+    ctrace!("Add audit log {:?}", audit_log);
+
+    conn.execute(
+        "INSERT INTO audit_log (time, user_name, address, method, params, result, error) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        &[
+            &audit_log.time,
+            &audit_log.user_name,
+            &audit_log.address,
+            &audit_log.method,
+            &audit_log.params,
+            &audit_log.result,
+            &audit_log.error,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn search(conn: &DBConnection, params: AuditQueryParams) -> postgres::Result<Vec<AuditLog>> {
+    ctrace!("Search audit log with {:?}", params);
+    let mut parameters = Parameters::default();
+    let mut where_conditions = Vec::new();
+    if !params.user_names.is_empty() {
+        let user_names_index = parameters.add(Rc::new(params.user_names.clone()));
+        where_conditions.push(format!("user_name = ANY(${})", user_names_index));
+    }
+    if !params.methods.is_empty() {
+        let methods_index = parameters.add(Rc::new(params.methods.clone()));
+        where_conditions.push(format!("method = ANY(${})", methods_index));
+    }
+    if let Some(search) = &params.search {
+        if search != "" {
+            let search_index = parameters.add(Rc::new(format!("%{}%", search)));
+            where_conditions.push(format!("params::text ILIKE ${}", search_index));
+        }
+    }
+    if let Some(from) = params.from_time {
+        let from_index = parameters.add(Rc::new(from));
+        where_conditions.push(format!("time > ${}", from_index));
+    }
+    if let Some(to) = params.to_time {
+        let to_index = parameters.add(Rc::new(to));
+        where_conditions.push(format!("time < ${}", to_index));
+    }
+    if params.failed_only {
+        where_conditions.push("error IS NOT NULL".to_string());
+    }
+    let where_clause = build_where_clause(&where_conditions);
+
+    let order_by = params.order_by.unwrap_or(OrderBy::DESC);
+    let order_by_clause = format!("ORDER BY time {0:?}, id {0:?}", order_by);
+
+    let limit = params.item_per_page.unwrap_or(100);
+    let limit_clause = format!("LIMIT {}", limit);
+
+    // page starts from 1
+    let offset = params.page.unwrap_or(1) - 1;
+    let offset_clause = format!("OFFSET {}", offset * limit);
+
+    let query_string = vec![
+        "SELECT time, user_name, address, method, params, result, error FROM audit_log",
+        &where_clause,
+        &order_by_clause,
+        &limit_clause,
+        &offset_clause,
+    ]
+    .join(" ");
+
+    let query_params: Vec<&dyn ToSql> = parameters.get().iter().map(Borrow::borrow).collect();
+    let rows = conn.query(&query_string, &query_params[..])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AuditLog {
+            time: row.get("time"),
+            user_name: row.get("user_name"),
+            address: row.get("address"),
+            method: row.get("method"),
+            params: row.get("params"),
+            result: row.get("result"),
+            error: row.get("error"),
+        })
+        .collect())
+}
@@ -0,0 +1,17 @@
+use super::super::types::DBConnection;
+use crate::util::{floor_to_5min, start_of_day, start_of_hour};
+
+pub fn insert(
+    conn: &DBConnection,
+    node_name: &str,
+    block_number: i64,
+    time: chrono::DateTime<chrono::Utc>,
+) -> postgres::Result<()> {
+    ctrace!("Add best block of {}", node_name);
+
+    conn.execute(
+        "INSERT INTO best_block (time, name, number, time_5min, time_hour, time_day) VALUES ($1, $2, $3, $4, $5, $6)",
+        &[&time, &node_name, &block_number, &floor_to_5min(&time), &start_of_hour(&time), &start_of_day(&time)],
+    )?;
+    Ok(())
+}
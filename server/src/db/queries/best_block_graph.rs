@@ -0,0 +1,44 @@
+use super::super::types::DBConnection;
+use super::network_usage_graph::get_sql_column_name_by_period;
+use crate::common_rpc_types::{GraphBlockLagAllRow, GraphCommonArgs};
+
+/// The height of a node in a period is the highest block it reported in the period,
+/// and its lag is the difference from the highest height among all nodes in the same period.
+pub fn query_block_lag_all(
+    conn: &DBConnection,
+    graph_args: GraphCommonArgs,
+) -> postgres::Result<Vec<GraphBlockLagAllRow>> {
+    let time_column_name = get_sql_column_name_by_period(graph_args.period);
+    let query_stmt = format!(
+        "\
+         WITH heights AS ( \
+         SELECT \
+         name, \
+         {0} AS period_time, \
+         MAX(number) AS height \
+         FROM best_block \
+         WHERE time<$1 AND time>$2 \
+         GROUP BY name, {0} \
+         ) \
+         SELECT \
+         name, \
+         period_time AS time, \
+         height, \
+         MAX(height) OVER (PARTITION BY period_time) - height AS lag \
+         FROM heights \
+         ORDER BY name, period_time",
+        time_column_name
+    );
+
+    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GraphBlockLagAllRow {
+            node_name: row.get("name"),
+            time: row.get("time"),
+            height: row.get("height"),
+            lag: row.get("lag"),
+        })
+        .collect())
+}
@@ -0,0 +1,48 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::HardwareInfo;
+use crate::util::{floor_to_5min, start_of_day, start_of_hour};
+
+/// Stores the usage ratio of the CPU, the memory and each disk as separate metrics.
+/// The CPU usage is the average of the cores, and the disks are named `disk0`, `disk1`, ...
+pub fn insert(
+    conn: &DBConnection,
+    node_name: &str,
+    hardware: &HardwareInfo,
+    time: chrono::DateTime<chrono::Utc>,
+) -> postgres::Result<()> {
+    ctrace!("Add hardware usage of {}", node_name);
+
+    let mut metrics: Vec<(String, f32)> = Vec::new();
+    if !hardware.cpu_usage.is_empty() {
+        let cpu_usage = hardware.cpu_usage.iter().sum::<f64>() / hardware.cpu_usage.len() as f64;
+        metrics.push(("cpu".to_string(), cpu_usage as f32));
+    }
+    if hardware.memory_usage.total != 0 {
+        metrics.push(("memory".to_string(), hardware.memory_usage.percentage_used as f32));
+    }
+    let disk_usages = match (&hardware.disk_usages, hardware.disk_usage) {
+        (Some(disk_usages), _) => disk_usages.clone(),
+        (None, Some(disk_usage)) => vec![disk_usage],
+        (None, None) => Vec::new(),
+    };
+    for (index, disk_usage) in disk_usages.iter().enumerate() {
+        metrics.push((format!("disk{}", index), disk_usage.percentage_used as f32));
+    }
+
+    let stmt = conn.prepare(
+        "INSERT INTO hardware_usage (time, name, metric, value, time_5min, time_hour, time_day) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+    )?;
+    for (metric, value) in metrics {
+        stmt.execute(&[
+            &time,
+            &node_name,
+            &metric,
+            &value,
+            &floor_to_5min(&time),
+            &start_of_hour(&time),
+            &start_of_day(&time),
+        ])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use super::super::types::DBConnection;
+use super::network_usage_graph::get_sql_column_name_by_period;
+use crate::common_rpc_types::{GraphCommonArgs, GraphHardwareAllRow, GraphHardwareNodeRow, NodeName};
+
+pub fn query_hardware_node(
+    conn: &DBConnection,
+    node_name: NodeName,
+    graph_args: GraphCommonArgs,
+) -> postgres::Result<Vec<GraphHardwareNodeRow>> {
+    let time_column_name = get_sql_column_name_by_period(graph_args.period);
+    let query_stmt = format!(
+        "\
+         SELECT \
+         metric, \
+         {0} AS time, \
+         CAST (AVG(value) AS REAL) AS value \
+         FROM hardware_usage \
+         WHERE time<$1 AND time>$2 \
+         AND name=$3 \
+         GROUP BY metric, {0} \
+         ORDER BY {0}, metric",
+        time_column_name
+    );
+
+    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from, &node_name])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GraphHardwareNodeRow {
+            metric: row.get("metric"),
+            time: row.get("time"),
+            value: row.get("value"),
+        })
+        .collect())
+}
+
+/// The disk usage of a node is the usage of its fullest disk.
+pub fn query_hardware_all(
+    conn: &DBConnection,
+    graph_args: GraphCommonArgs,
+) -> postgres::Result<Vec<GraphHardwareAllRow>> {
+    let time_column_name = get_sql_column_name_by_period(graph_args.period);
+    let query_stmt = format!(
+        "\
+         SELECT \
+         name, \
+         metric_group AS metric, \
+         time, \
+         CAST (MAX(value) AS REAL) AS value \
+         FROM ( \
+         SELECT \
+         name, \
+         CASE WHEN metric LIKE 'disk%' THEN 'disk' ELSE metric END AS metric_group, \
+         {0} AS time, \
+         AVG(value) AS value \
+         FROM hardware_usage \
+         WHERE time<$1 AND time>$2 \
+         GROUP BY name, metric, {0} \
+         ) AS usage_per_metric \
+         GROUP BY name, metric_group, time \
+         ORDER BY name, time, metric",
+        time_column_name
+    );
+
+    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GraphHardwareAllRow {
+            node_name: row.get("name"),
+            metric: row.get("metric"),
+            time: row.get("time"),
+            value: row.get("value"),
+        })
+        .collect())
+}
@@ -0,0 +1,141 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::{Job, JobRun};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Returns the id of the new job. The id of the argument is ignored.
+pub fn insert(conn: &DBConnection, job: &Job) -> postgres::Result<i64> {
+    ctrace!("Add job {:?}", job);
+
+    let rows = conn.query(
+        "INSERT INTO jobs (name, user_name, nodes, action, schedule, enabled, next_run_at, created_at) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+        &[
+            &job.name,
+            &job.user_name,
+            &to_json(&job.nodes),
+            &to_json(&job.action),
+            &to_json(&job.schedule),
+            &job.enabled,
+            &job.next_run_at,
+            &job.created_at,
+        ],
+    )?;
+    Ok(rows.get(0).get("id"))
+}
+
+/// Only `enabled` and `next_run_at` can be changed.
+pub fn update(conn: &DBConnection, job: &Job) -> postgres::Result<()> {
+    ctrace!("Update job {:?}", job);
+
+    conn.execute("UPDATE jobs SET enabled=$2, next_run_at=$3 WHERE id=$1", &[&job.id, &job.enabled, &job.next_run_at])?;
+    Ok(())
+}
+
+/// Updates `enabled` and `next_run_at` only if the job is enabled and its next run is still `scheduled_at`.
+/// Returns whether the job was updated.
+pub fn claim(conn: &DBConnection, job: &Job, scheduled_at: DateTime<Utc>) -> postgres::Result<bool> {
+    ctrace!("Claim the run of job {} at {}", job.id, scheduled_at);
+
+    let updated =
+        conn.execute("UPDATE jobs SET enabled=$2, next_run_at=$3 WHERE id=$1 AND enabled AND next_run_at=$4", &[
+            &job.id,
+            &job.enabled,
+            &job.next_run_at,
+            &scheduled_at,
+        ])?;
+    Ok(updated == 1)
+}
+
+/// Returns whether the job existed. The runs of the job are kept.
+pub fn delete(conn: &DBConnection, id: i64) -> postgres::Result<bool> {
+    ctrace!("Delete job {}", id);
+
+    let deleted = conn.execute("DELETE FROM jobs WHERE id=$1", &[&id])?;
+    Ok(deleted > 0)
+}
+
+pub fn get(conn: &DBConnection, id: i64) -> postgres::Result<Option<Job>> {
+    ctrace!("Query job {}", id);
+
+    let rows = conn.query(&format!("{} WHERE id=$1", SELECT_JOBS), &[&id])?;
+    Ok(rows.iter().next().and_then(|row| row_to_job(&row)))
+}
+
+pub fn list(conn: &DBConnection) -> postgres::Result<Vec<Job>> {
+    ctrace!("Query jobs");
+
+    let rows = conn.query(&format!("{} ORDER BY id", SELECT_JOBS), &[])?;
+    Ok(rows.iter().filter_map(|row| row_to_job(&row)).collect())
+}
+
+pub fn insert_run(conn: &DBConnection, run: &JobRun) -> postgres::Result<()> {
+    ctrace!("Add job run {:?}", run);
+
+    conn.execute(
+        "INSERT INTO job_runs (job_id, job_name, started_at, finished_at, results, error) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+        &[&run.job_id, &run.job_name, &run.started_at, &run.finished_at, &to_json(&run.results), &run.error],
+    )?;
+    Ok(())
+}
+
+/// Returns the recent runs of the job, or of every job if `job_id` is None, the latest first.
+pub fn list_runs(conn: &DBConnection, job_id: Option<i64>, limit: i64) -> postgres::Result<Vec<JobRun>> {
+    ctrace!("Query runs of job {:?}", job_id);
+
+    let rows = conn.query(
+        "SELECT id, job_id, job_name, started_at, finished_at, results, error FROM job_runs \
+         WHERE $1::BIGINT IS NULL OR job_id=$1 ORDER BY started_at DESC LIMIT $2",
+        &[&job_id, &limit],
+    )?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| match serde_json::from_value(row.get("results")) {
+            Ok(results) => Some(JobRun {
+                id: row.get("id"),
+                job_id: row.get("job_id"),
+                job_name: row.get("job_name"),
+                started_at: row.get("started_at"),
+                finished_at: row.get("finished_at"),
+                results,
+                error: row.get("error"),
+            }),
+            Err(err) => {
+                cwarn!("Invalid job run: {}", err);
+                None
+            }
+        })
+        .collect())
+}
+
+const SELECT_JOBS: &str =
+    "SELECT id, name, user_name, nodes, action, schedule, enabled, next_run_at, created_at FROM jobs";
+
+fn to_json<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("Jobs are always serializable")
+}
+
+fn row_to_job(row: &postgres::rows::Row) -> Option<Job> {
+    match parse_job(row) {
+        Ok(job) => Some(job),
+        Err(err) => {
+            cwarn!("Invalid job: {}", err);
+            None
+        }
+    }
+}
+
+fn parse_job(row: &postgres::rows::Row) -> Result<Job, serde_json::Error> {
+    Ok(Job {
+        id: row.get("id"),
+        name: row.get("name"),
+        user_name: row.get("user_name"),
+        nodes: serde_json::from_value(row.get("nodes"))?,
+        action: serde_json::from_value(row.get("action"))?,
+        schedule: serde_json::from_value(row.get("schedule"))?,
+        enabled: row.get("enabled"),
+        next_run_at: row.get("next_run_at"),
+        created_at: row.get("created_at"),
+    })
+}
@@ -1,5 +1,6 @@
-use super::super::super::common_rpc_types::StructuredLog;
-use super::super::types::{DBConnection, Log, LogQueryParams, OrderBy};
+use super::super::super::common_rpc_types::{GraphPeriod, StructuredLog};
+use super::super::types::{DBConnection, Log, LogHistogramRow, LogQueryParams, LogSearchMode, OrderBy};
+use super::period::period_time_expression;
 use postgres::types::ToSql;
 use std::borrow::Borrow;
 use std::rc::Rc;
@@ -51,12 +52,117 @@ pub fn insert(conn: &DBConnection, node_name: &str, logs: Vec<StructuredLog>) ->
 pub fn search(conn: &DBConnection, params: LogQueryParams) -> postgres::Result<Vec<Log>> {
     ctrace!("Search log with {:?}", params);
     let mut parameters = Parameters::default();
+    let (where_conditions, text_query) = build_where_conditions(&params, &mut parameters);
+    let where_clause = build_where_clause(&where_conditions);
+
+    let order_by = params.order_by.unwrap_or(OrderBy::ASC);
+    let order_by_clause = match text_query {
+        Some(text_query) => format!("ORDER BY ts_rank(message_tsv, {}) DESC, timestamp {:?}", text_query, order_by),
+        None => format!("ORDER BY timestamp {:?}", order_by),
+    };
+
+    let limit = params.item_per_page.unwrap_or(100);
+    let limit_clause = format!("LIMIT {}", limit);
+
+    // page starts from 1
+    let offset = params.page.unwrap_or(1) - 1;
+    let offset_clause = format!("OFFSET {}", offset * limit);
+
+    let query_string = vec![SELECT_LOGS, &where_clause, &order_by_clause, &limit_clause, &offset_clause].join(" ");
+
+    let query_params: Vec<&dyn ToSql> = parameters.get().iter().map(Borrow::borrow).collect();
+    let rows = conn.query(&query_string, &query_params[..])?;
+
+    Ok(rows.into_iter().map(|row| row_to_log(&row)).collect())
+}
+
+/// Returns the logs right after `after`, the (timestamp, id) of the last log of the previous call.
+/// Unlike `search`, the result doesn't shift while new logs are inserted, so it is used to read every matching log.
+/// The logs are ordered by the timestamp even in the full-text search modes.
+pub fn search_after(
+    conn: &DBConnection,
+    params: LogQueryParams,
+    after: Option<(chrono::DateTime<chrono::Local>, i64)>,
+    limit: i64,
+) -> postgres::Result<Vec<Log>> {
+    ctrace!("Search log after {:?} with {:?}", after, params);
+    let mut parameters = Parameters::default();
+    let (mut where_conditions, _) = build_where_conditions(&params, &mut parameters);
+
+    let order_by = params.order_by.unwrap_or(OrderBy::ASC);
+    if let Some((timestamp, id)) = after {
+        let comparator = match order_by {
+            OrderBy::ASC => ">",
+            OrderBy::DESC => "<",
+        };
+        let timestamp_index = parameters.add(Rc::new(timestamp));
+        let id_index = parameters.add(Rc::new(id));
+        where_conditions.push(format!("(timestamp, id) {} (${}, ${})", comparator, timestamp_index, id_index));
+    }
+    let where_clause = build_where_clause(&where_conditions);
+    let order_by_clause = format!("ORDER BY timestamp {0:?}, id {0:?}", order_by);
+    let limit_clause = format!("LIMIT {}", limit);
+
+    let query_string = vec![SELECT_LOGS, &where_clause, &order_by_clause, &limit_clause].join(" ");
+
+    let query_params: Vec<&dyn ToSql> = parameters.get().iter().map(Borrow::borrow).collect();
+    let rows = conn.query(&query_string, &query_params[..])?;
+
+    Ok(rows.into_iter().map(|row| row_to_log(&row)).collect())
+}
+
+/// Counts the logs matching `params` for each period, node, level and target. The paging options are ignored.
+pub fn histogram(
+    conn: &DBConnection,
+    params: LogQueryParams,
+    period: GraphPeriod,
+) -> postgres::Result<Vec<LogHistogramRow>> {
+    ctrace!("Get log histogram with {:?}", params);
+    let mut parameters = Parameters::default();
+    let (where_conditions, _) = build_where_conditions(&params, &mut parameters);
+    let where_clause = build_where_clause(&where_conditions);
+
+    let query_string = format!(
+        "SELECT \
+         {0} AS period_time, \
+         name, level, target, COUNT(*) AS count \
+         FROM logs {1} \
+         GROUP BY period_time, name, level, target \
+         ORDER BY period_time, name, level, target",
+        period_time_expression(period, "timestamp"),
+        where_clause
+    );
+
+    let query_params: Vec<&dyn ToSql> = parameters.get().iter().map(Borrow::borrow).collect();
+    let rows = conn.query(&query_string, &query_params[..])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| LogHistogramRow {
+            time: row.get("period_time"),
+            node_name: row.get("name"),
+            level: row.get("level"),
+            target: row.get("target"),
+            count: row.get("count"),
+        })
+        .collect())
+}
+
+const SELECT_LOGS: &str = "SELECT id, name, level, target, thread_name, message, timestamp FROM logs";
+
+/// Returns the conditions and, in the full-text search modes, the tsquery expression to rank the logs.
+fn build_where_conditions(params: &LogQueryParams, parameters: &mut Parameters) -> (Vec<String>, Option<String>) {
+    let mut text_query = None;
     let mut where_conditions = Vec::new();
-    if let Some(filter) = params.filter {
+    if let Some(filter) = &params.filter {
         if !filter.node_names.is_empty() {
-            let node_names_index = parameters.add(Rc::new(filter.node_names));
+            let node_names_index = parameters.add(Rc::new(filter.node_names.clone()));
             where_conditions.push(format!("name = ANY(${})", node_names_index));
         }
+        if !filter.tags.is_empty() {
+            let tags_index = parameters.add(Rc::new(filter.tags.clone()));
+            where_conditions.push(format!("name IN (SELECT name FROM node_tags WHERE tag = ANY(${}))", tags_index));
+        }
         if !filter.levels.is_empty() {
             let uppercase_levels: Vec<String> =
                 filter.levels.iter().map(|level| level.to_string().to_uppercase()).collect();
@@ -64,21 +170,45 @@ pub fn search(conn: &DBConnection, params: LogQueryParams) -> postgres::Result<V
             where_conditions.push(format!("level = ANY(${})", filters_index));
         }
         if !filter.targets.is_empty() {
-            let targets_index = parameters.add(Rc::new(filter.targets));
+            let targets_index = parameters.add(Rc::new(filter.targets.clone()));
             where_conditions.push(format!("target = ANY(${})", targets_index));
         }
-        if let Some(thread_name) = filter.thread_name {
-            let target_index = parameters.add(Rc::new(thread_name));
+        if let Some(thread_name) = &filter.thread_name {
+            let target_index = parameters.add(Rc::new(thread_name.clone()));
             where_conditions.push(format!("thread_name = ${}", target_index));
         }
     }
-    if let Some(search) = params.search {
+    if let Some(search) = &params.search {
         if search != "" {
-            let search_index = parameters.add(Rc::new(format!("%{}%", search)));
-            where_conditions.push(format!("message ILIKE ${}", search_index));
+            match params.search_mode {
+                LogSearchMode::Substring => {
+                    let search_index = parameters.add(Rc::new(format!("%{}%", search)));
+                    where_conditions.push(format!("message ILIKE ${}", search_index));
+                }
+                LogSearchMode::Phrase => {
+                    let search_index = parameters.add(Rc::new(search.clone()));
+                    text_query = Some(format!("phraseto_tsquery('simple', ${})", search_index));
+                }
+                LogSearchMode::Prefix => {
+                    if let Some(query) = prefix_query(search) {
+                        let search_index = parameters.add(Rc::new(query));
+                        text_query = Some(format!("to_tsquery('simple', ${})", search_index));
+                    } else {
+                        // A search without any word matches nothing, rather than every log.
+                        where_conditions.push("FALSE".to_string());
+                    }
+                }
+                LogSearchMode::Boolean => {
+                    let search_index = parameters.add(Rc::new(search.clone()));
+                    text_query = Some(format!("websearch_to_tsquery('simple', ${})", search_index));
+                }
+            }
+            if let Some(text_query) = &text_query {
+                where_conditions.push(format!("message_tsv @@ {}", text_query));
+            }
         }
     }
-    if let Some(time) = params.time {
+    if let Some(time) = &params.time {
         if let Some(from) = time.from_time {
             let from_index = parameters.add(Rc::new(from));
             where_conditions.push(format!("timestamp > ${}", from_index));
@@ -88,44 +218,46 @@ pub fn search(conn: &DBConnection, params: LogQueryParams) -> postgres::Result<V
             where_conditions.push(format!("timestamp < ${}", to_index));
         }
     }
+    (where_conditions, text_query)
+}
+
+/// Builds a tsquery matching the words starting with each of the given words.
+/// The characters having a meaning in tsquery are dropped, so the result is always a valid tsquery.
+fn prefix_query(search: &str) -> Option<String> {
+    let words: Vec<String> = search
+        .split_whitespace()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric() || *c == '_').collect::<String>())
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word))
+        .collect();
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.join(" & "))
+    }
+}
 
-    let where_clause = if !where_conditions.is_empty() {
+pub fn build_where_clause(where_conditions: &[String]) -> String {
+    if !where_conditions.is_empty() {
         "WHERE ".to_string() + &where_conditions.join(" AND ")
     } else {
         "".to_string()
-    };
-
-    let order_by = params.order_by.unwrap_or(OrderBy::ASC);
-    let order_by_clause = format!("ORDER BY timestamp {:?}", order_by);
-
-    let limit = params.item_per_page.unwrap_or(100);
-    let limit_clause = format!("LIMIT {}", limit);
-
-    // page starts from 1
-    let offset = params.page.unwrap_or(1) - 1;
-    let offset_clause = format!("OFFSET {}", offset * limit);
-
-    let query_string =
-        vec!["SELECT * FROM logs", &where_clause, &order_by_clause, &limit_clause, &offset_clause].join(" ");
-
-    let query_params: Vec<&dyn ToSql> = parameters.get().iter().map(Borrow::borrow).collect();
-    let rows = conn.query(&query_string, &query_params[..])?;
+    }
+}
 
-    Ok(rows
-        .into_iter()
-        .map(|row| Log {
-            id: row.get("id"),
-            node_name: row.get("name"),
-            level: row.get("level"),
-            target: row.get("target"),
-            timestamp: row.get("timestamp"),
-            message: format!("{} {}", row.get::<_, String>("thread_name"), row.get::<_, String>("message")),
-        })
-        .collect())
+fn row_to_log(row: &postgres::rows::Row) -> Log {
+    Log {
+        id: row.get("id"),
+        node_name: row.get("name"),
+        level: row.get("level"),
+        target: row.get("target"),
+        timestamp: row.get("timestamp"),
+        message: format!("{} {}", row.get::<_, String>("thread_name"), row.get::<_, String>("message")),
+    }
 }
 
 #[derive(Default)]
-struct Parameters {
+pub struct Parameters {
     parameters: Vec<Rc<dyn ToSql>>,
 }
 
@@ -160,3 +292,33 @@ pub fn get_targets(conn: &DBConnection) -> postgres::Result<Vec<String>> {
     )?;
     Ok(rows.iter().map(|row| row.get("target")).collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_query_drops_operators() {
+        assert_eq!(Some("0xab12:*".to_string()), prefix_query("0xab12"));
+        assert_eq!(Some("imported:* & block:*".to_string()), prefix_query(" imported  block! "));
+        assert_eq!(Some("a:* & b:*".to_string()), prefix_query("a:* | !b"));
+        assert_eq!(None, prefix_query("& | !"));
+    }
+
+    #[test]
+    fn prefix_search_without_words_matches_nothing() {
+        let params = LogQueryParams {
+            filter: None,
+            search: Some("& | !".to_string()),
+            search_mode: LogSearchMode::Prefix,
+            time: None,
+            page: None,
+            item_per_page: None,
+            order_by: None,
+        };
+        let mut parameters = Parameters::default();
+        let (where_conditions, text_query) = build_where_conditions(&params, &mut parameters);
+        assert_eq!(vec!["FALSE".to_string()], where_conditions);
+        assert_eq!(None, text_query);
+    }
+}
@@ -0,0 +1,71 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::MaterializedViewRefreshRow;
+use chrono::{DateTime, Utc};
+
+pub const MATERIALIZED_VIEWS: &[&str] = &[
+    "time_5min_report_view_materialized",
+    "time_5min_avg_report_view_materialized",
+    "time_5min_extension_report_view_materialized",
+    "time_5min_peer_report_view_materialized",
+];
+
+/// Refreshes the view without locking out the graph queries reading it.
+pub fn refresh_concurrently(conn: &DBConnection, view: &str) -> postgres::Result<()> {
+    ctrace!("Refresh {}", view);
+
+    conn.execute(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {}", view), &[])?;
+    Ok(())
+}
+
+/// Records the result of a refresh. `error` is None if the refresh succeeded.
+pub fn record_refresh(
+    conn: &DBConnection,
+    view: &str,
+    run_at: DateTime<Utc>,
+    duration_ms: i64,
+    error: Option<&str>,
+) -> postgres::Result<()> {
+    ctrace!("Record the refresh of {}", view);
+
+    let failed = if error.is_some() {
+        1
+    } else {
+        0
+    };
+    conn.execute(
+        "INSERT INTO materialized_view_refreshes \
+         (view, last_run_at, last_duration_ms, last_success_at, last_error, consecutive_failures, total_failures) \
+         VALUES ($1, $2, $3, CASE WHEN $5::INTEGER = 0 THEN $2 END, $4, $5::INTEGER, $5::INTEGER) \
+         ON CONFLICT (view) DO UPDATE SET \
+         last_run_at=EXCLUDED.last_run_at, \
+         last_duration_ms=EXCLUDED.last_duration_ms, \
+         last_success_at=COALESCE(EXCLUDED.last_success_at, materialized_view_refreshes.last_success_at), \
+         last_error=EXCLUDED.last_error, \
+         consecutive_failures=CASE WHEN $5::INTEGER = 0 THEN 0 \
+         ELSE materialized_view_refreshes.consecutive_failures + 1 END, \
+         total_failures=materialized_view_refreshes.total_failures + $5::INTEGER",
+        &[&view, &run_at, &duration_ms, &error, &failed],
+    )?;
+    Ok(())
+}
+
+pub fn get_refreshes(conn: &DBConnection) -> postgres::Result<Vec<MaterializedViewRefreshRow>> {
+    let rows = conn.query(
+        "SELECT view, last_run_at, last_duration_ms, last_success_at, last_error, consecutive_failures, total_failures \
+         FROM materialized_view_refreshes ORDER BY view",
+        &[],
+    )?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| MaterializedViewRefreshRow {
+            view: row.get("view"),
+            last_run_at: row.get("last_run_at"),
+            last_duration_ms: row.get("last_duration_ms"),
+            last_success_at: row.get("last_success_at"),
+            last_error: row.get("last_error"),
+            consecutive_failures: row.get("consecutive_failures"),
+            total_failures: row.get("total_failures"),
+        })
+        .collect())
+}
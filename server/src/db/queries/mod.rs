@@ -1,6 +1,20 @@
+pub mod audit_log;
+pub mod best_block;
+pub mod best_block_graph;
 pub mod client_extra;
 pub mod config;
+pub mod hardware_usage;
+pub mod hardware_usage_graph;
+pub mod jobs;
 pub mod logs;
+pub mod materialized_view;
 pub mod network_usage;
 pub mod network_usage_graph;
+pub mod node_status_history;
+pub mod node_tags;
 pub mod peer_count;
+pub mod peer_count_graph;
+pub mod period;
+pub mod retention;
+pub mod rollouts;
+pub mod users;
@@ -15,9 +15,11 @@ pub fn query_network_out_all(
                       time_5min, \
                       value \
                       FROM time_5min_report_view_materialized \
-                      WHERE time_5min<$1 and time_5min>$2";
+                      WHERE time_5min<$1 and time_5min>$2 \
+                      AND (cardinality($3::VARCHAR[]) = 0 \
+                      OR name IN (SELECT name FROM node_tags WHERE tag = ANY($3)))";
 
-    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from])?;
+    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from, &graph_args.tags])?;
 
     Ok(rows
         .into_iter()
@@ -29,7 +31,7 @@ pub fn query_network_out_all(
         .collect())
 }
 
-fn get_sql_column_name_by_period(period: GraphPeriod) -> &'static str {
+pub fn get_sql_column_name_by_period(period: GraphPeriod) -> &'static str {
     match period {
         GraphPeriod::Minutes5 => "time_5min",
         GraphPeriod::Hour => "time_hour",
@@ -48,9 +50,11 @@ pub fn query_network_out_all_avg(
                       time_5min, \
                       value \
                       FROM time_5min_avg_report_view_materialized \
-                      WHERE time_5min<$1 and time_5min>$2";
+                      WHERE time_5min<$1 and time_5min>$2 \
+                      AND (cardinality($3::VARCHAR[]) = 0 \
+                      OR name IN (SELECT name FROM node_tags WHERE tag = ANY($3)))";
 
-    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from])?;
+    let rows = conn.query(&query_stmt, &[&graph_args.to, &graph_args.from, &graph_args.tags])?;
 
     Ok(rows
         .into_iter()
@@ -0,0 +1,68 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::{NodeName, NodeStatus, NodeStatusHistoryRow};
+use chrono::{DateTime, Utc};
+
+pub fn insert(conn: &DBConnection, node_name: &str, status: NodeStatus, time: DateTime<Utc>) -> postgres::Result<()> {
+    ctrace!("Add status {} of {}", status, node_name);
+
+    conn.execute("INSERT INTO node_status_history (name, status, time) VALUES ($1, $2, $3)", &[
+        &node_name,
+        &status.to_string(),
+        &time,
+    ])?;
+    Ok(())
+}
+
+/// Records every node whose latest status is not `Error` as disconnected at `time`, and returns the number of them.
+/// The server can't see the agents while it is stopped, so the nodes are disconnected from its start.
+pub fn disconnect_all(conn: &DBConnection, time: DateTime<Utc>) -> postgres::Result<u64> {
+    ctrace!("Record all nodes as disconnected at {}", time);
+
+    conn.execute(
+        "INSERT INTO node_status_history (name, status, time) \
+         SELECT name, CAST($1 AS VARCHAR), $2 FROM \
+         (SELECT DISTINCT ON (name) name, status FROM node_status_history ORDER BY name, time DESC) AS latest \
+         WHERE status <> CAST($1 AS VARCHAR)",
+        &[&NodeStatus::Error.to_string(), &time],
+    )
+}
+
+/// Returns the status transitions between `from` and `to`.
+/// The first row is the status that the node had at `from`, if it is known.
+pub fn query(
+    conn: &DBConnection,
+    node_name: NodeName,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> postgres::Result<Vec<NodeStatusHistoryRow>> {
+    let mut history = Vec::new();
+
+    let rows = conn
+        .query("SELECT status FROM node_status_history WHERE name=$1 AND time<=$2 ORDER BY time DESC LIMIT 1", &[
+            &node_name, &from,
+        ])?;
+    if !rows.is_empty() {
+        history.push(NodeStatusHistoryRow {
+            status: parse_status(&rows.get(0).get::<_, String>("status")),
+            time: from,
+        });
+    }
+
+    let rows = conn.query(
+        "SELECT status, time FROM node_status_history WHERE name=$1 AND time>$2 AND time<$3 ORDER BY time",
+        &[&node_name, &from, &to],
+    )?;
+    history.extend(rows.into_iter().map(|row| NodeStatusHistoryRow {
+        status: parse_status(&row.get::<_, String>("status")),
+        time: row.get("time"),
+    }));
+
+    Ok(history)
+}
+
+fn parse_status(status: &str) -> NodeStatus {
+    status.parse().unwrap_or_else(|err| {
+        cwarn!("{}", err);
+        NodeStatus::UFO
+    })
+}
@@ -0,0 +1,27 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::NodeName;
+use std::collections::HashMap;
+
+pub fn get_all(conn: &DBConnection) -> postgres::Result<HashMap<NodeName, Vec<String>>> {
+    ctrace!("Query node tags");
+
+    let rows = conn.query("SELECT name, tag FROM node_tags ORDER BY name, tag", &[])?;
+    let mut tags: HashMap<NodeName, Vec<String>> = HashMap::new();
+    for row in rows.iter() {
+        tags.entry(row.get("name")).or_default().push(row.get("tag"));
+    }
+    Ok(tags)
+}
+
+/// Replaces the tags of the node.
+pub fn set(conn: &DBConnection, name: &str, tags: &[String]) -> postgres::Result<()> {
+    ctrace!("Set the tags of {} to {:?}", name, tags);
+
+    let transaction = conn.transaction()?;
+    transaction.execute("DELETE FROM node_tags WHERE name=$1", &[&name])?;
+    for tag in tags {
+        transaction
+            .execute("INSERT INTO node_tags (name, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING", &[&name, tag])?;
+    }
+    transaction.commit()
+}
@@ -0,0 +1,59 @@
+use super::super::types::DBConnection;
+use super::period::period_time_expression;
+use crate::common_rpc_types::{GraphCommonArgs, GraphPeerCountAllRow, GraphPeerCountNodeRow, NodeName};
+
+pub fn query_peer_count_node(
+    conn: &DBConnection,
+    node_name: NodeName,
+    graph_args: GraphCommonArgs,
+) -> postgres::Result<Vec<GraphPeerCountNodeRow>> {
+    let query_string = format!(
+        "\
+         SELECT \
+         {} AS period_time, \
+         CAST (AVG(peer_count) AS REAL) AS value \
+         FROM peer_count \
+         WHERE time<$1 AND time>$2 \
+         AND name=$3 \
+         GROUP BY period_time \
+         ORDER BY period_time",
+        period_time_expression(graph_args.period, "time")
+    );
+    let rows = conn.query(&query_string, &[&graph_args.to, &graph_args.from, &node_name])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GraphPeerCountNodeRow {
+            time: row.get("period_time"),
+            value: row.get("value"),
+        })
+        .collect())
+}
+
+pub fn query_peer_count_all(
+    conn: &DBConnection,
+    graph_args: GraphCommonArgs,
+) -> postgres::Result<Vec<GraphPeerCountAllRow>> {
+    let query_string = format!(
+        "\
+         SELECT \
+         name, \
+         {} AS period_time, \
+         CAST (AVG(peer_count) AS REAL) AS value \
+         FROM peer_count \
+         WHERE time<$1 AND time>$2 \
+         GROUP BY name, period_time \
+         ORDER BY name, period_time",
+        period_time_expression(graph_args.period, "time")
+    );
+    let rows = conn.query(&query_string, &[&graph_args.to, &graph_args.from])?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| GraphPeerCountAllRow {
+            node_name: row.get("name"),
+            time: row.get("period_time"),
+            value: row.get("value"),
+        })
+        .collect())
+}
@@ -0,0 +1,17 @@
+use crate::common_rpc_types::GraphPeriod;
+
+/// Truncates the column to the start of its period, for the tables that don't have the period columns.
+/// It truncates in UTC as util::floor_to_5min, util::start_of_hour and util::start_of_day do for the period columns
+/// of the other tables, so the graphs share their buckets.
+pub fn period_time_expression(period: GraphPeriod, column: &str) -> String {
+    let utc_time = format!("({} AT TIME ZONE 'UTC')", column);
+    let truncated = match period {
+        GraphPeriod::Minutes5 => format!(
+            "date_trunc('hour', {0}) + make_interval(mins => CAST(extract(minute FROM {0}) AS INTEGER) / 5 * 5)",
+            utc_time
+        ),
+        GraphPeriod::Hour => format!("date_trunc('hour', {})", utc_time),
+        GraphPeriod::Day => format!("date_trunc('day', {})", utc_time),
+    };
+    format!("(({}) AT TIME ZONE 'UTC')", truncated)
+}
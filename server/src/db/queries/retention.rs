@@ -0,0 +1,86 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::TableStatsRow;
+
+/// The tables pruned by the retention policies, with their time columns.
+pub const TABLES: &[(&str, &str)] = &[
+    ("network_usage", "time"),
+    ("peer_count", "time"),
+    ("hardware_usage", "time"),
+    ("best_block", "time"),
+    ("node_status_history", "time"),
+    ("logs", "timestamp"),
+];
+
+/// Deletes at most `batch_size` rows older than `time` and returns the number of deleted rows.
+pub fn delete_older_rows(
+    conn: &DBConnection,
+    table: &str,
+    time: chrono::DateTime<chrono::Utc>,
+    batch_size: i64,
+) -> postgres::Result<u64> {
+    ctrace!("Remove {} older than {}", table, time);
+
+    let result =
+        conn.execute(&format!("DELETE FROM {0} WHERE id IN (SELECT id FROM {0} WHERE time<$1 LIMIT $2)", table), &[
+            &time,
+            &batch_size,
+        ])?;
+    ctrace!("Delete result {}", result);
+    Ok(result)
+}
+
+pub fn delete_older_logs_of_level(
+    conn: &DBConnection,
+    level: &str,
+    time: chrono::DateTime<chrono::Utc>,
+    batch_size: i64,
+) -> postgres::Result<u64> {
+    ctrace!("Remove {} logs older than {}", level, time);
+
+    let result = conn.execute(
+        "DELETE FROM logs WHERE id IN (SELECT id FROM logs WHERE level=$1 AND timestamp<$2 LIMIT $3)",
+        &[&level, &time, &batch_size],
+    )?;
+    ctrace!("Delete result {}", result);
+    Ok(result)
+}
+
+pub fn delete_older_logs_except_levels(
+    conn: &DBConnection,
+    levels: &[String],
+    time: chrono::DateTime<chrono::Utc>,
+    batch_size: i64,
+) -> postgres::Result<u64> {
+    ctrace!("Remove logs older than {} except {:?}", time, levels);
+
+    let result = conn.execute(
+        "DELETE FROM logs WHERE id IN \
+         (SELECT id FROM logs WHERE NOT (level = ANY($1)) AND timestamp<$2 LIMIT $3)",
+        &[&levels, &time, &batch_size],
+    )?;
+    ctrace!("Delete result {}", result);
+    Ok(result)
+}
+
+/// The row count is the planner's estimate, which is updated by VACUUM and ANALYZE.
+pub fn table_stats(conn: &DBConnection, table: &str, time_column: &str) -> postgres::Result<TableStatsRow> {
+    let rows = conn.query(
+        "SELECT pg_total_relation_size(oid) AS size, CAST(reltuples AS BIGINT) AS estimated_row_count \
+         FROM pg_class WHERE relname=$1 AND relkind='r'",
+        &[&table],
+    )?;
+    let (size, estimated_row_count) = if rows.is_empty() {
+        (0, 0)
+    } else {
+        let row = rows.get(0);
+        (row.get("size"), row.get("estimated_row_count"))
+    };
+
+    let rows = conn.query(&format!("SELECT MIN({}) AS oldest FROM {}", time_column, table), &[])?;
+    Ok(TableStatsRow {
+        table_name: table.to_string(),
+        size,
+        estimated_row_count,
+        oldest: rows.get(0).get("oldest"),
+    })
+}
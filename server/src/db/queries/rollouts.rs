@@ -0,0 +1,88 @@
+use super::super::types::DBConnection;
+use crate::common_rpc_types::{Rollout, RolloutStatus};
+use serde_json::Value;
+
+/// Returns the id of the new rollout. The id of the argument is ignored.
+pub fn insert(conn: &DBConnection, rollout: &Rollout) -> postgres::Result<i64> {
+    ctrace!("Add rollout {:?}", rollout);
+
+    let rows = conn.query(
+        "INSERT INTO rollouts (user_name, status, request, nodes, started_at, updated_at) \
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+        &[
+            &rollout.user_name,
+            &rollout.status.to_string(),
+            &to_json(&rollout.request),
+            &to_json(&rollout.nodes),
+            &rollout.started_at,
+            &rollout.updated_at,
+        ],
+    )?;
+    Ok(rows.get(0).get("id"))
+}
+
+pub fn update(conn: &DBConnection, rollout: &Rollout) -> postgres::Result<()> {
+    ctrace!("Update rollout {:?}", rollout);
+
+    conn.execute("UPDATE rollouts SET status=$2, nodes=$3, updated_at=$4 WHERE id=$1", &[
+        &rollout.id,
+        &rollout.status.to_string(),
+        &to_json(&rollout.nodes),
+        &rollout.updated_at,
+    ])?;
+    Ok(())
+}
+
+pub fn get(conn: &DBConnection, id: i64) -> postgres::Result<Option<Rollout>> {
+    ctrace!("Query rollout {}", id);
+
+    let rows = conn.query(&format!("{} WHERE id=$1", SELECT_ROLLOUTS), &[&id])?;
+    Ok(rows.iter().next().and_then(|row| row_to_rollout(&row)))
+}
+
+/// Returns the recent rollouts, the latest first.
+pub fn list(conn: &DBConnection, limit: i64) -> postgres::Result<Vec<Rollout>> {
+    ctrace!("Query rollouts");
+
+    let rows = conn.query(&format!("{} ORDER BY id DESC LIMIT $1", SELECT_ROLLOUTS), &[&limit])?;
+    Ok(rows.iter().filter_map(|row| row_to_rollout(&row)).collect())
+}
+
+/// The rollouts that were running when the server stopped cannot be continued.
+pub fn abort_unfinished(conn: &DBConnection) -> postgres::Result<u64> {
+    ctrace!("Abort unfinished rollouts");
+
+    conn.execute("UPDATE rollouts SET status=$1, updated_at=now() WHERE status=$2 OR status=$3", &[
+        &RolloutStatus::Aborted.to_string(),
+        &RolloutStatus::Running.to_string(),
+        &RolloutStatus::Paused.to_string(),
+    ])
+}
+
+const SELECT_ROLLOUTS: &str = "SELECT id, user_name, status, request, nodes, started_at, updated_at FROM rollouts";
+
+fn to_json<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("Rollouts are always serializable")
+}
+
+fn row_to_rollout(row: &postgres::rows::Row) -> Option<Rollout> {
+    match parse_rollout(row) {
+        Ok(rollout) => Some(rollout),
+        Err(err) => {
+            cwarn!("Invalid rollout: {}", err);
+            None
+        }
+    }
+}
+
+fn parse_rollout(row: &postgres::rows::Row) -> Result<Rollout, String> {
+    Ok(Rollout {
+        id: row.get("id"),
+        user_name: row.get("user_name"),
+        status: row.get::<_, String>("status").parse()?,
+        request: serde_json::from_value(row.get("request")).map_err(|err| err.to_string())?,
+        nodes: serde_json::from_value(row.get("nodes")).map_err(|err| err.to_string())?,
+        started_at: row.get("started_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
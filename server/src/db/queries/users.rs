@@ -0,0 +1,74 @@
+use super::super::types::{DBConnection, Session, User};
+use crate::auth::Role;
+
+pub fn get(conn: &DBConnection, name: &str) -> postgres::Result<Option<User>> {
+    ctrace!("Query user by name {}", name);
+
+    let rows = conn.query("SELECT name, password_hash, role FROM users WHERE name=$1", &[&name])?;
+    if rows.is_empty() {
+        return Ok(None)
+    }
+    let row = rows.get(0);
+    Ok(parse_role(&row).map(|role| User {
+        name: row.get("name"),
+        password_hash: row.get("password_hash"),
+        role,
+    }))
+}
+
+pub fn insert_session(conn: &DBConnection, session: &Session) -> postgres::Result<()> {
+    ctrace!("Insert session of {}", session.user_name);
+
+    conn.execute("INSERT INTO sessions (token, user_name, expires_at) VALUES ($1, $2, $3)", &[
+        &session.token,
+        &session.user_name,
+        &session.expires_at,
+    ])?;
+    Ok(())
+}
+
+/// Returns the session only when it is not expired. The role is read from the user, so a role change is applied at once.
+pub fn get_session(conn: &DBConnection, token: &str) -> postgres::Result<Option<Session>> {
+    ctrace!("Query session");
+
+    let rows = conn.query(
+        "SELECT sessions.token, sessions.user_name, sessions.expires_at, users.role \
+         FROM sessions JOIN users ON sessions.user_name=users.name \
+         WHERE sessions.token=$1 AND sessions.expires_at > now()",
+        &[&token],
+    )?;
+    if rows.is_empty() {
+        return Ok(None)
+    }
+    let row = rows.get(0);
+    Ok(parse_role(&row).map(|role| Session {
+        token: row.get("token"),
+        user_name: row.get("user_name"),
+        role,
+        expires_at: row.get("expires_at"),
+    }))
+}
+
+pub fn delete_session(conn: &DBConnection, token: &str) -> postgres::Result<()> {
+    ctrace!("Delete session");
+
+    conn.execute("DELETE FROM sessions WHERE token=$1", &[&token])?;
+    Ok(())
+}
+
+pub fn delete_expired_sessions(conn: &DBConnection) -> postgres::Result<u64> {
+    ctrace!("Delete expired sessions");
+
+    conn.execute("DELETE FROM sessions WHERE expires_at <= now()", &[])
+}
+
+fn parse_role(row: &postgres::rows::Row) -> Option<Role> {
+    let role: String = row.get("role");
+    match role.parse() {
+        Ok(role) => Some(role),
+        Err(err) => {
+            cwarn!("{}", err);
+            None
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use super::super::common_rpc_types::{
-    GraphCommonArgs, GraphNetworkOutAllAVGRow, GraphNetworkOutAllRow, GraphNetworkOutNodeExtensionRow,
-    GraphNetworkOutNodePeerRow, NetworkUsage, NodeName, NodeStatus, StructuredLog,
+    GraphBlockLagAllRow, GraphCommonArgs, GraphHardwareAllRow, GraphHardwareNodeRow, GraphNetworkOutAllAVGRow,
+    GraphNetworkOutAllRow, GraphNetworkOutNodeExtensionRow, GraphNetworkOutNodePeerRow, GraphPeerCountAllRow,
+    GraphPeerCountNodeRow, GraphPeriod, HardwareInfo, Job, JobRun, MaterializedViewRefreshRow, NetworkUsage, NodeName,
+    NodeStatus, NodeStatusHistoryRow, Rollout, StructuredLog, TableStatsRow,
 };
+use super::super::config::DBConfig;
 use super::super::{common_rpc_types as rpc_type, util};
 use super::event::{Event, EventSubscriber};
 use super::queries;
 use super::types::{
-    ClientExtra, ClientQueryResult, Connection, Connections, DBConnection, Error as DBError, Log, LogQueryParams,
+    AuditLog, AuditQueryParams, ClientExtra, ClientQueryResult, Connection, Connections, DBConnection,
+    Error as DBError, Log, LogHistogramRow, LogQueryParams, Session, User,
 };
 use r2d2_postgres::PostgresConnectionManager;
 use std::collections::hash_map::Entry;
@@ -16,11 +20,17 @@ use std::net::SocketAddr;
 use std::sync::mpsc::{channel, Sender};
 use std::thread;
 
+/// The number of rollouts returned by `get_rollouts`
+const RECENT_ROLLOUTS: i64 = 50;
+/// The number of job runs returned by `get_job_runs`
+const RECENT_JOB_RUNS: i64 = 100;
+
 #[derive(Debug, Clone)]
 pub enum Message {
     CheckConnection(Sender<Result<(), DBError>>),
     InitializeClient(Box<ClientQueryResult>, Sender<bool>),
     UpdateClient(Box<ClientQueryResult>),
+    DisconnectClient(NodeName, Option<SocketAddr>),
     GetClient(NodeName, Sender<Option<ClientQueryResult>>),
     GetClients(Sender<Vec<ClientQueryResult>>),
     GetConnections(Sender<Vec<rpc_type::Connection>>),
@@ -39,6 +49,49 @@ pub enum Message {
         Sender<Result<Vec<GraphNetworkOutNodeExtensionRow>, DBError>>,
     ),
     GetGraphNetworkOutNodePeer(NodeName, GraphCommonArgs, Sender<Result<Vec<GraphNetworkOutNodePeerRow>, DBError>>),
+    GetNodeStatusHistory(
+        NodeName,
+        chrono::DateTime<chrono::Utc>,
+        chrono::DateTime<chrono::Utc>,
+        Sender<Result<Vec<NodeStatusHistoryRow>, DBError>>,
+    ),
+    WriteHardwareUsage(NodeName, HardwareInfo, chrono::DateTime<chrono::Utc>),
+    GetGraphHardwareNode(NodeName, GraphCommonArgs, Sender<Result<Vec<GraphHardwareNodeRow>, DBError>>),
+    GetGraphHardwareAll(GraphCommonArgs, Sender<Result<Vec<GraphHardwareAllRow>, DBError>>),
+    WriteBestBlock(NodeName, i64, chrono::DateTime<chrono::Utc>),
+    GetGraphBlockLagAll(GraphCommonArgs, Sender<Result<Vec<GraphBlockLagAllRow>, DBError>>),
+    GetGraphPeerCountNode(NodeName, GraphCommonArgs, Sender<Result<Vec<GraphPeerCountNodeRow>, DBError>>),
+    GetGraphPeerCountAll(GraphCommonArgs, Sender<Result<Vec<GraphPeerCountAllRow>, DBError>>),
+    GetTableStats(Sender<Result<Vec<TableStatsRow>, DBError>>),
+    GetLogsAfter(
+        LogQueryParams,
+        Option<(chrono::DateTime<chrono::Local>, i64)>,
+        i64,
+        Sender<Result<Vec<Log>, DBError>>,
+    ),
+    GetLogHistogram(LogQueryParams, GraphPeriod, Sender<Result<Vec<LogHistogramRow>, DBError>>),
+    GetUser(String, Sender<Result<Option<User>, DBError>>),
+    CreateSession(Session, Sender<Result<(), DBError>>),
+    GetSession(String, Sender<Result<Option<Session>, DBError>>),
+    DeleteSession(String, Sender<Result<(), DBError>>),
+    WriteAuditLog(Box<AuditLog>),
+    GetAuditLogs(AuditQueryParams, Sender<Result<Vec<AuditLog>, DBError>>),
+    CreateRollout(Box<Rollout>, Sender<Result<i64, DBError>>),
+    UpdateRollout(Box<Rollout>),
+    GetRollout(i64, Sender<Result<Option<Rollout>, DBError>>),
+    GetRollouts(Sender<Result<Vec<Rollout>, DBError>>),
+    GetNodeTags(Sender<HashMap<NodeName, Vec<String>>>),
+    GetTagsOfNode(NodeName, Sender<Vec<String>>),
+    SetNodeTags(NodeName, Vec<String>, Sender<Result<(), DBError>>),
+    CreateJob(Box<Job>, Sender<Result<i64, DBError>>),
+    UpdateJob(Box<Job>, Sender<Result<(), DBError>>),
+    ClaimJob(Box<Job>, chrono::DateTime<chrono::Utc>, Sender<Result<bool, DBError>>),
+    DeleteJob(i64, Sender<Result<bool, DBError>>),
+    GetJob(i64, Sender<Result<Option<Job>, DBError>>),
+    GetJobs(Sender<Result<Vec<Job>, DBError>>),
+    WriteJobRun(Box<JobRun>),
+    GetJobRuns(Option<i64>, Sender<Result<Vec<JobRun>, DBError>>),
+    GetMaterializedViewRefreshes(Sender<Result<Vec<MaterializedViewRefreshRow>, DBError>>),
 }
 
 #[derive(Clone)]
@@ -50,6 +103,7 @@ pub struct ServiceSender {
 struct State {
     client_query_result: HashMap<NodeName, ClientQueryResult>,
     connection: Connections,
+    node_tags: HashMap<NodeName, Vec<String>>,
 }
 
 pub struct Service {
@@ -60,30 +114,29 @@ pub struct Service {
 
 pub struct ServiceNewArg {
     pub event_subscriber: Box<dyn EventSubscriber>,
-    pub db_user: String,
-    pub db_password: String,
+    pub db_config: DBConfig,
 }
 
 impl Service {
     fn new(
         ServiceNewArg {
             event_subscriber,
-            db_user,
-            db_password,
+            db_config,
         }: ServiceNewArg,
     ) -> Self {
-        let manager = PostgresConnectionManager::new(
-            format!("postgres://{}:{}@localhost", db_user, db_password),
-            r2d2_postgres::TlsMode::None,
-        )
-        .expect("Create connection manager");
+        let manager = PostgresConnectionManager::new(db_config.url(), r2d2_postgres::TlsMode::None)
+            .expect("Create connection manager");
         let pool = r2d2::Pool::new(manager).expect("Create connection pool");
 
         let connection = pool.get().expect("Get connection");
         queries::config::set_query_timeout(&connection).unwrap();
+        let node_tags = queries::node_tags::get_all(&connection).expect("Read node tags");
 
         Self {
-            state: State::default(),
+            state: State {
+                node_tags,
+                ..State::default()
+            },
             event_subscriber,
             pool,
         }
@@ -95,6 +148,16 @@ impl Service {
         let service_sender = ServiceSender::new(tx);
 
         let mut service = Service::new(arg);
+        match service.abort_unfinished_rollouts() {
+            Ok(0) => {}
+            Ok(count) => cwarn!("{} rollouts were not finished before the restart and are aborted", count),
+            Err(err) => cerror!("Cannot abort the unfinished rollouts: {}", err),
+        }
+        match service.disconnect_all_clients() {
+            Ok(0) => {}
+            Ok(count) => cinfo!("{} nodes are recorded as disconnected until their agents connect again", count),
+            Err(err) => cerror!("Cannot record the nodes as disconnected: {}", err),
+        }
 
         thread::Builder::new()
             .name("db service".to_string())
@@ -110,6 +173,9 @@ impl Service {
                         Message::UpdateClient(client_query_result) => {
                             service.update_client(*client_query_result);
                         }
+                        Message::DisconnectClient(name, address) => {
+                            service.disconnect_client(name, address);
+                        }
                         Message::GetClient(node_name, callback) => {
                             service.get_client(&node_name, callback);
                         }
@@ -181,6 +247,216 @@ impl Service {
                                 cerror!("Error at {}", callback_err);
                             }
                         }
+                        Message::GetNodeStatusHistory(node_name, from, to, callback) => {
+                            let result = service
+                                .get_node_status_history(node_name, from, to)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::WriteHardwareUsage(node_name, hardware, time) => {
+                            util::log_error(&node_name, service.write_hardware_usage(&node_name, &hardware, time));
+                        }
+                        Message::GetGraphHardwareNode(node_name, args, callback) => {
+                            let result = service
+                                .get_hardware_node_graph(node_name, args)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetGraphHardwareAll(args, callback) => {
+                            let result =
+                                service.get_hardware_all_graph(args).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::WriteBestBlock(node_name, block_number, time) => {
+                            util::log_error(&node_name, service.write_best_block(&node_name, block_number, time));
+                        }
+                        Message::GetGraphBlockLagAll(args, callback) => {
+                            let result =
+                                service.get_block_lag_all_graph(args).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetGraphPeerCountNode(node_name, args, callback) => {
+                            let result = service
+                                .get_peer_count_node_graph(node_name, args)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetGraphPeerCountAll(args, callback) => {
+                            let result = service
+                                .get_peer_count_all_graph(args)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetTableStats(callback) => {
+                            let result = service.get_table_stats().map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetLogsAfter(params, after, limit, callback) => {
+                            let result = service
+                                .get_logs_after(params, after, limit)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetLogHistogram(params, period, callback) => {
+                            let result = service
+                                .get_log_histogram(params, period)
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetUser(name, callback) => {
+                            let result = service.get_user(&name).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::CreateSession(session, callback) => {
+                            let result =
+                                service.create_session(&session).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetSession(token, callback) => {
+                            let result = service.get_session(&token).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::DeleteSession(token, callback) => {
+                            let result =
+                                service.delete_session(&token).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::WriteAuditLog(audit_log) => {
+                            if let Err(err) = service.write_audit_log(&audit_log) {
+                                cerror!("Cannot write the audit log {:?}: {}", audit_log, err);
+                            }
+                        }
+                        Message::GetAuditLogs(params, callback) => {
+                            let result =
+                                service.get_audit_logs(params).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::CreateRollout(rollout, callback) => {
+                            let result =
+                                service.create_rollout(&rollout).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::UpdateRollout(rollout) => {
+                            if let Err(err) = service.update_rollout(&rollout) {
+                                cerror!("Cannot update the rollout {}: {}", rollout.id, err);
+                            }
+                        }
+                        Message::GetRollout(id, callback) => {
+                            let result = service.get_rollout(id).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetRollouts(callback) => {
+                            let result = service.get_rollouts().map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetNodeTags(callback) => {
+                            if let Err(callback_err) = callback.send(service.state.node_tags.clone()) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetTagsOfNode(name, callback) => {
+                            let tags = service.state.node_tags.get(&name).cloned().unwrap_or_default();
+                            if let Err(callback_err) = callback.send(tags) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::SetNodeTags(name, tags, callback) => {
+                            let result =
+                                service.set_node_tags(name, tags).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::CreateJob(job, callback) => {
+                            let result = service.create_job(&job).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::UpdateJob(job, callback) => {
+                            let result = service.update_job(&job).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::ClaimJob(job, scheduled_at, callback) => {
+                            let result =
+                                service.claim_job(&job, scheduled_at).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::DeleteJob(id, callback) => {
+                            let result = service.delete_job(id).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetJob(id, callback) => {
+                            let result = service.get_job(id).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetJobs(callback) => {
+                            let result = service.get_jobs().map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::WriteJobRun(run) => {
+                            if let Err(err) = service.write_job_run(&run) {
+                                cerror!("Cannot write the job run {:?}: {}", run, err);
+                            }
+                        }
+                        Message::GetJobRuns(job_id, callback) => {
+                            let result = service.get_job_runs(job_id).map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::GetMaterializedViewRefreshes(callback) => {
+                            let result = service
+                                .get_materialized_view_refreshes()
+                                .map_err(|err| DBError::Internal(err.to_string()));
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
                     }
                 }
             })
@@ -217,6 +493,7 @@ impl Service {
                     after: state.clone().into(),
                 });
                 e.insert(state.clone());
+                util::log_error(&state.name, self.write_status_history(&state.name, None, state.status));
                 if let Err(err) = callback.send(true) {
                     cerror!("Cannot send callback : {}", err);
                 }
@@ -240,7 +517,9 @@ impl Service {
             before: None.into(),
             after: state.clone().into(),
         });
+        let before_status = before.status;
         *before = state.clone();
+        util::log_error(&state.name, self.write_status_history(&state.name, Some(before_status), state.status));
         if let Err(err) = callback.send(true) {
             cerror!("Cannot send callback : {}", err);
         }
@@ -268,7 +547,23 @@ impl Service {
         }
 
         let before = self.state.client_query_result.get_mut(&name).expect("Checked");
+        let before_status = before.status;
+        let after_status = after.status;
         *before = after;
+        util::log_error(&name, self.write_status_history(&name, Some(before_status), after_status));
+    }
+
+    fn write_status_history(
+        &self,
+        node_name: &str,
+        before: Option<NodeStatus>,
+        after: NodeStatus,
+    ) -> Result<(), Box<dyn error::Error>> {
+        if before == Some(after) {
+            return Ok(())
+        }
+        queries::node_status_history::insert(&self.db_conn()?, node_name, after, chrono::Utc::now())?;
+        Ok(())
     }
 
     fn socket_addrs_to_name(&self, addrs: &Connection) -> Option<rpc_type::Connection> {
@@ -348,7 +643,15 @@ impl Service {
     }
 
     fn write_logs(&self, node_name: &str, logs: Vec<StructuredLog>) -> Result<(), Box<dyn error::Error>> {
-        queries::logs::insert(&self.db_conn()?, node_name, logs)?;
+        if logs.is_empty() {
+            return Ok(())
+        }
+        queries::logs::insert(&self.db_conn()?, node_name, logs.clone())?;
+        self.event_subscriber.on_event(Event::LogsWritten {
+            name: node_name.to_string(),
+            tags: self.state.node_tags.get(node_name).cloned().unwrap_or_default(),
+            logs,
+        });
         Ok(())
     }
 
@@ -411,6 +714,239 @@ impl Service {
         let rows = queries::network_usage_graph::query_network_out_node_peer(&self.db_conn()?, node_name, args)?;
         Ok(rows)
     }
+
+    fn get_node_status_history(
+        &self,
+        node_name: NodeName,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<NodeStatusHistoryRow>, Box<dyn error::Error>> {
+        let rows = queries::node_status_history::query(&self.db_conn()?, node_name, from, to)?;
+        Ok(rows)
+    }
+
+    fn write_hardware_usage(
+        &self,
+        node_name: &str,
+        hardware: &HardwareInfo,
+        time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        queries::hardware_usage::insert(&self.db_conn()?, node_name, hardware, time)?;
+        Ok(())
+    }
+
+    fn get_hardware_node_graph(
+        &self,
+        node_name: NodeName,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphHardwareNodeRow>, Box<dyn error::Error>> {
+        let rows = queries::hardware_usage_graph::query_hardware_node(&self.db_conn()?, node_name, args)?;
+        Ok(rows)
+    }
+
+    fn get_hardware_all_graph(&self, args: GraphCommonArgs) -> Result<Vec<GraphHardwareAllRow>, Box<dyn error::Error>> {
+        let rows = queries::hardware_usage_graph::query_hardware_all(&self.db_conn()?, args)?;
+        Ok(rows)
+    }
+
+    fn write_best_block(
+        &self,
+        node_name: &str,
+        block_number: i64,
+        time: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        queries::best_block::insert(&self.db_conn()?, node_name, block_number, time)?;
+        Ok(())
+    }
+
+    fn get_block_lag_all_graph(
+        &self,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphBlockLagAllRow>, Box<dyn error::Error>> {
+        let rows = queries::best_block_graph::query_block_lag_all(&self.db_conn()?, args)?;
+        Ok(rows)
+    }
+
+    fn get_peer_count_node_graph(
+        &self,
+        node_name: NodeName,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphPeerCountNodeRow>, Box<dyn error::Error>> {
+        let rows = queries::peer_count_graph::query_peer_count_node(&self.db_conn()?, node_name, args)?;
+        Ok(rows)
+    }
+
+    fn get_peer_count_all_graph(
+        &self,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphPeerCountAllRow>, Box<dyn error::Error>> {
+        let rows = queries::peer_count_graph::query_peer_count_all(&self.db_conn()?, args)?;
+        Ok(rows)
+    }
+
+    fn get_table_stats(&self) -> Result<Vec<TableStatsRow>, Box<dyn error::Error>> {
+        let conn = self.db_conn()?;
+        let mut rows = Vec::new();
+        for (table, time_column) in queries::retention::TABLES {
+            rows.push(queries::retention::table_stats(&conn, table, time_column)?);
+        }
+        Ok(rows)
+    }
+
+    fn get_logs_after(
+        &self,
+        params: LogQueryParams,
+        after: Option<(chrono::DateTime<chrono::Local>, i64)>,
+        limit: i64,
+    ) -> Result<Vec<Log>, Box<dyn error::Error>> {
+        let logs = queries::logs::search_after(&self.db_conn()?, params, after, limit)?;
+        Ok(logs)
+    }
+
+    fn get_log_histogram(
+        &self,
+        params: LogQueryParams,
+        period: GraphPeriod,
+    ) -> Result<Vec<LogHistogramRow>, Box<dyn error::Error>> {
+        let rows = queries::logs::histogram(&self.db_conn()?, params, period)?;
+        Ok(rows)
+    }
+
+    fn get_user(&self, name: &str) -> Result<Option<User>, Box<dyn error::Error>> {
+        let user = queries::users::get(&self.db_conn()?, name)?;
+        Ok(user)
+    }
+
+    fn create_session(&self, session: &Session) -> Result<(), Box<dyn error::Error>> {
+        let conn = self.db_conn()?;
+        queries::users::delete_expired_sessions(&conn)?;
+        queries::users::insert_session(&conn, session)?;
+        Ok(())
+    }
+
+    fn get_session(&self, token: &str) -> Result<Option<Session>, Box<dyn error::Error>> {
+        let session = queries::users::get_session(&self.db_conn()?, token)?;
+        Ok(session)
+    }
+
+    fn delete_session(&self, token: &str) -> Result<(), Box<dyn error::Error>> {
+        queries::users::delete_session(&self.db_conn()?, token)?;
+        Ok(())
+    }
+
+    fn write_audit_log(&self, audit_log: &AuditLog) -> Result<(), Box<dyn error::Error>> {
+        queries::audit_log::insert(&self.db_conn()?, audit_log)?;
+        Ok(())
+    }
+
+    fn get_audit_logs(&self, params: AuditQueryParams) -> Result<Vec<AuditLog>, Box<dyn error::Error>> {
+        let audit_logs = queries::audit_log::search(&self.db_conn()?, params)?;
+        Ok(audit_logs)
+    }
+
+    fn disconnect_all_clients(&self) -> Result<u64, Box<dyn error::Error>> {
+        let count = queries::node_status_history::disconnect_all(&self.db_conn()?, chrono::Utc::now())?;
+        Ok(count)
+    }
+
+    /// The disconnection is recorded in the status history as `Error`, so the uptime of the node ends there.
+    fn disconnect_client(&mut self, name: NodeName, address: Option<SocketAddr>) {
+        if !self.state.client_query_result.contains_key(&name) {
+            return
+        }
+        self.update_client(ClientQueryResult {
+            name,
+            status: NodeStatus::Error,
+            address,
+            ..Default::default()
+        });
+    }
+
+    fn abort_unfinished_rollouts(&self) -> Result<u64, Box<dyn error::Error>> {
+        let count = queries::rollouts::abort_unfinished(&self.db_conn()?)?;
+        Ok(count)
+    }
+
+    fn create_rollout(&self, rollout: &Rollout) -> Result<i64, Box<dyn error::Error>> {
+        let id = queries::rollouts::insert(&self.db_conn()?, rollout)?;
+        Ok(id)
+    }
+
+    fn update_rollout(&self, rollout: &Rollout) -> Result<(), Box<dyn error::Error>> {
+        queries::rollouts::update(&self.db_conn()?, rollout)?;
+        Ok(())
+    }
+
+    fn get_rollout(&self, id: i64) -> Result<Option<Rollout>, Box<dyn error::Error>> {
+        let rollout = queries::rollouts::get(&self.db_conn()?, id)?;
+        Ok(rollout)
+    }
+
+    fn get_rollouts(&self) -> Result<Vec<Rollout>, Box<dyn error::Error>> {
+        let rollouts = queries::rollouts::list(&self.db_conn()?, RECENT_ROLLOUTS)?;
+        Ok(rollouts)
+    }
+
+    fn set_node_tags(&mut self, name: NodeName, mut tags: Vec<String>) -> Result<(), Box<dyn error::Error>> {
+        tags.sort();
+        tags.dedup();
+        queries::node_tags::set(&self.db_conn()?, &name, &tags)?;
+        if tags.is_empty() {
+            self.state.node_tags.remove(&name);
+        } else {
+            self.state.node_tags.insert(name.clone(), tags.clone());
+        }
+        self.event_subscriber.on_event(Event::NodeTagsUpdated {
+            name,
+            tags,
+        });
+        Ok(())
+    }
+
+    fn create_job(&self, job: &Job) -> Result<i64, Box<dyn error::Error>> {
+        let id = queries::jobs::insert(&self.db_conn()?, job)?;
+        Ok(id)
+    }
+
+    fn update_job(&self, job: &Job) -> Result<(), Box<dyn error::Error>> {
+        queries::jobs::update(&self.db_conn()?, job)?;
+        Ok(())
+    }
+
+    fn claim_job(&self, job: &Job, scheduled_at: chrono::DateTime<chrono::Utc>) -> Result<bool, Box<dyn error::Error>> {
+        let claimed = queries::jobs::claim(&self.db_conn()?, job, scheduled_at)?;
+        Ok(claimed)
+    }
+
+    fn delete_job(&self, id: i64) -> Result<bool, Box<dyn error::Error>> {
+        let deleted = queries::jobs::delete(&self.db_conn()?, id)?;
+        Ok(deleted)
+    }
+
+    fn get_job(&self, id: i64) -> Result<Option<Job>, Box<dyn error::Error>> {
+        let job = queries::jobs::get(&self.db_conn()?, id)?;
+        Ok(job)
+    }
+
+    fn get_jobs(&self) -> Result<Vec<Job>, Box<dyn error::Error>> {
+        let jobs = queries::jobs::list(&self.db_conn()?)?;
+        Ok(jobs)
+    }
+
+    fn write_job_run(&self, run: &JobRun) -> Result<(), Box<dyn error::Error>> {
+        queries::jobs::insert_run(&self.db_conn()?, run)?;
+        Ok(())
+    }
+
+    fn get_job_runs(&self, job_id: Option<i64>) -> Result<Vec<JobRun>, Box<dyn error::Error>> {
+        let runs = queries::jobs::list_runs(&self.db_conn()?, job_id, RECENT_JOB_RUNS)?;
+        Ok(runs)
+    }
+
+    fn get_materialized_view_refreshes(&self) -> Result<Vec<MaterializedViewRefreshRow>, Box<dyn error::Error>> {
+        let rows = queries::materialized_view::get_refreshes(&self.db_conn()?)?;
+        Ok(rows)
+    }
 }
 
 impl ServiceSender {
@@ -439,6 +975,10 @@ impl ServiceSender {
         self.sender.send(Message::UpdateClient(client_query_result.into())).expect("Should success update client");
     }
 
+    pub fn disconnect_client(&self, name: NodeName, address: Option<SocketAddr>) {
+        self.sender.send(Message::DisconnectClient(name, address)).expect("Should success disconnect client");
+    }
+
     pub fn get_client_query_result(&self, name: &str) -> Result<Option<ClientQueryResult>, DBError> {
         let (tx, rx) = channel();
         self.sender.send(Message::GetClient(name.to_string(), tx)).expect("Should success send request");
@@ -544,4 +1084,224 @@ impl ServiceSender {
             .expect("Should success send request");
         rx.recv()?
     }
+
+    pub fn get_node_status_history(
+        &self,
+        node_name: NodeName,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Vec<NodeStatusHistoryRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetNodeStatusHistory(node_name, from, to, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn write_hardware_usage(
+        &self,
+        node_name: NodeName,
+        hardware: HardwareInfo,
+        time: chrono::DateTime<chrono::Utc>,
+    ) {
+        self.sender.send(Message::WriteHardwareUsage(node_name, hardware, time)).expect("Should success send request");
+    }
+
+    pub fn get_hardware_node_graph(
+        &self,
+        node_name: NodeName,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphHardwareNodeRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetGraphHardwareNode(node_name, args, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_hardware_all_graph(&self, args: GraphCommonArgs) -> Result<Vec<GraphHardwareAllRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetGraphHardwareAll(args, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn write_best_block(&self, node_name: NodeName, block_number: i64, time: chrono::DateTime<chrono::Utc>) {
+        self.sender.send(Message::WriteBestBlock(node_name, block_number, time)).expect("Should success send request");
+    }
+
+    pub fn get_block_lag_all_graph(&self, args: GraphCommonArgs) -> Result<Vec<GraphBlockLagAllRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetGraphBlockLagAll(args, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_peer_count_node_graph(
+        &self,
+        node_name: NodeName,
+        args: GraphCommonArgs,
+    ) -> Result<Vec<GraphPeerCountNodeRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetGraphPeerCountNode(node_name, args, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_peer_count_all_graph(&self, args: GraphCommonArgs) -> Result<Vec<GraphPeerCountAllRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetGraphPeerCountAll(args, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_table_stats(&self) -> Result<Vec<TableStatsRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetTableStats(tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_logs_after(
+        &self,
+        params: LogQueryParams,
+        after: Option<(chrono::DateTime<chrono::Local>, i64)>,
+        limit: i64,
+    ) -> Result<Vec<Log>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetLogsAfter(params, after, limit, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_log_histogram(
+        &self,
+        params: LogQueryParams,
+        period: GraphPeriod,
+    ) -> Result<Vec<LogHistogramRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetLogHistogram(params, period, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_user(&self, name: String) -> Result<Option<User>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetUser(name, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn create_session(&self, session: Session) -> Result<(), DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::CreateSession(session, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_session(&self, token: String) -> Result<Option<Session>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetSession(token, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn delete_session(&self, token: String) -> Result<(), DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::DeleteSession(token, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn write_audit_log(&self, audit_log: AuditLog) {
+        self.sender.send(Message::WriteAuditLog(Box::new(audit_log))).expect("Should success send request");
+    }
+
+    pub fn get_audit_logs(&self, params: AuditQueryParams) -> Result<Vec<AuditLog>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetAuditLogs(params, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn create_rollout(&self, rollout: Rollout) -> Result<i64, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::CreateRollout(Box::new(rollout), tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn update_rollout(&self, rollout: Rollout) {
+        self.sender.send(Message::UpdateRollout(Box::new(rollout))).expect("Should success send request");
+    }
+
+    pub fn get_rollout(&self, id: i64) -> Result<Option<Rollout>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetRollout(id, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    /// Returns the recent rollouts, the latest first
+    pub fn get_rollouts(&self) -> Result<Vec<Rollout>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetRollouts(tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    /// Returns the tags of the nodes having any tag
+    pub fn get_node_tags(&self) -> Result<HashMap<NodeName, Vec<String>>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetNodeTags(tx)).expect("Should success send request");
+        Ok(rx.recv()?)
+    }
+
+    pub fn get_tags_of_node(&self, name: NodeName) -> Result<Vec<String>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetTagsOfNode(name, tx)).expect("Should success send request");
+        Ok(rx.recv()?)
+    }
+
+    pub fn set_node_tags(&self, name: NodeName, tags: Vec<String>) -> Result<(), DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::SetNodeTags(name, tags, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn create_job(&self, job: Job) -> Result<i64, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::CreateJob(Box::new(job), tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn update_job(&self, job: Job) -> Result<(), DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::UpdateJob(Box::new(job), tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    /// Stores the next run of the job only if the job is still enabled and scheduled at `scheduled_at`.
+    /// Returns false if the job was disabled, deleted or claimed by another run in the meantime.
+    pub fn claim_job(&self, job: Job, scheduled_at: chrono::DateTime<chrono::Utc>) -> Result<bool, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::ClaimJob(Box::new(job), scheduled_at, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn delete_job(&self, id: i64) -> Result<bool, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::DeleteJob(id, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_job(&self, id: i64) -> Result<Option<Job>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetJob(id, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_jobs(&self) -> Result<Vec<Job>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetJobs(tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn write_job_run(&self, run: JobRun) {
+        self.sender.send(Message::WriteJobRun(Box::new(run))).expect("Should success send request");
+    }
+
+    /// Returns the recent runs of the job, or of every job if `job_id` is None, the latest first
+    pub fn get_job_runs(&self, job_id: Option<i64>) -> Result<Vec<JobRun>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetJobRuns(job_id, tx)).expect("Should success send request");
+        rx.recv()?
+    }
+
+    pub fn get_materialized_view_refreshes(&self) -> Result<Vec<MaterializedViewRefreshRow>, DBError> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::GetMaterializedViewRefreshes(tx)).expect("Should success send request");
+        rx.recv()?
+    }
 }
@@ -1,6 +1,7 @@
 use super::super::common_rpc_types::{
-    BlackList, BlockId, HardwareInfo, NodeName, NodeStatus, NodeVersion, PendingTransaction, WhiteList,
+    BlackList, BlockId, HardwareInfo, NodeName, NodeStatus, NodeVersion, PendingTransaction, StructuredLog, WhiteList,
 };
+use crate::auth::Role;
 use serde::export::Formatter;
 use serde_derive::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
@@ -122,21 +123,71 @@ impl Connections {
 pub struct LogQueryParams {
     pub filter: Option<LogFilter>,
     pub search: Option<String>,
+    #[serde(default)]
+    pub search_mode: LogSearchMode,
     pub time: Option<LogDuration>,
     pub page: Option<i32>,
     pub item_per_page: Option<i32>,
     pub order_by: Option<OrderBy>,
 }
 
+/// `Substring` matches a part of the message. The others use the full-text index and order the logs by the rank.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LogSearchMode {
+    Substring,
+    /// The words in the given order, e.g. `imported block`
+    Phrase,
+    /// The words starting with the given words, e.g. `0xab12` for a block hash
+    Prefix,
+    /// Quoted phrases, `or` and `-` for exclusion, e.g. `"new block" -sealing`
+    Boolean,
+}
+
+impl Default for LogSearchMode {
+    fn default() -> Self {
+        LogSearchMode::Substring
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LogFilter {
     pub node_names: Vec<String>,
+    /// Selects the nodes having any of the tags
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub levels: Vec<LogLevel>,
     pub targets: Vec<String>,
     pub thread_name: Option<String>,
 }
 
+impl LogFilter {
+    /// Empty conditions match every log, as in `logs::search`.
+    pub fn matches(&self, node_name: &str, node_tags: &[String], log: &StructuredLog) -> bool {
+        if !self.node_names.is_empty() && !self.node_names.iter().any(|name| name == node_name) {
+            return false
+        }
+        if !self.tags.is_empty() && !node_tags.iter().any(|tag| self.tags.contains(tag)) {
+            return false
+        }
+        if !self.levels.is_empty()
+            && !self.levels.iter().any(|level| level.to_string().eq_ignore_ascii_case(&log.level))
+        {
+            return false
+        }
+        if !self.targets.is_empty() && !self.targets.contains(&log.target) {
+            return false
+        }
+        if let Some(thread_name) = &self.thread_name {
+            if thread_name != &log.thread_name {
+                return false
+            }
+        }
+        true
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum LogLevel {
@@ -177,6 +228,62 @@ pub struct Log {
     pub message: String,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogHistogramRow {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub node_name: String,
+    pub level: String,
+    pub target: String,
+    pub count: i64,
+}
+
+/// A call of a mutating frontend RPC. `result` is set when the call succeeded, and `error` otherwise.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLog {
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub user_name: Option<String>,
+    pub address: Option<String>,
+    pub method: String,
+    pub params: serde_json::Value,
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditQueryParams {
+    #[serde(default)]
+    pub user_names: Vec<String>,
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// A part of the parameters, such as a node name
+    pub search: Option<String>,
+    pub from_time: Option<chrono::DateTime<chrono::Utc>>,
+    pub to_time: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub failed_only: bool,
+    pub page: Option<i32>,
+    pub item_per_page: Option<i32>,
+    pub order_by: Option<OrderBy>,
+}
+
+#[derive(Debug, Clone)]
+pub struct User {
+    pub name: String,
+    pub password_hash: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    pub token: String,
+    pub user_name: String,
+    pub role: Role,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Clone)]
 pub enum Error {
     Internal(String),
@@ -197,3 +304,59 @@ impl std::fmt::Display for Error {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(level: &str, target: &str) -> StructuredLog {
+        StructuredLog {
+            level: level.to_string(),
+            target: target.to_string(),
+            message: "message".to_string(),
+            timestamp: "2019-01-01T00:00:00.000000+00:00".to_string(),
+            thread_name: "main".to_string(),
+        }
+    }
+
+    #[test]
+    fn empty_filter_matches_every_log() {
+        let filter = LogFilter {
+            node_names: Vec::new(),
+            tags: Vec::new(),
+            levels: Vec::new(),
+            targets: Vec::new(),
+            thread_name: None,
+        };
+        assert!(filter.matches("node", &[], &log("TRACE", "miner")));
+    }
+
+    #[test]
+    fn filter_by_node_and_level() {
+        let filter = LogFilter {
+            node_names: vec!["node".to_string()],
+            tags: Vec::new(),
+            levels: vec![LogLevel::Error, LogLevel::Warn],
+            targets: Vec::new(),
+            thread_name: None,
+        };
+        assert!(filter.matches("node", &[], &log("ERROR", "miner")));
+        assert!(filter.matches("node", &[], &log("WARN", "sync")));
+        assert!(!filter.matches("node", &[], &log("INFO", "miner")));
+        assert!(!filter.matches("other", &[], &log("ERROR", "miner")));
+    }
+
+    #[test]
+    fn filter_by_tag() {
+        let filter = LogFilter {
+            node_names: Vec::new(),
+            tags: vec!["validator".to_string(), "canary".to_string()],
+            levels: Vec::new(),
+            targets: Vec::new(),
+            thread_name: None,
+        };
+        assert!(filter.matches("node", &["seoul".to_string(), "canary".to_string()], &log("INFO", "miner")));
+        assert!(!filter.matches("node", &["observer".to_string()], &log("INFO", "miner")));
+        assert!(!filter.matches("node", &[], &log("INFO", "miner")));
+    }
+}
@@ -1,3 +1,4 @@
+use super::common_rpc_types::NodeName;
 use super::{db, frontend, jsonrpc};
 use serde_json::{self, json, Value};
 
@@ -11,6 +12,12 @@ impl EventPropagator {
             frontend_service,
         }
     }
+
+    fn send_event(&self, event_type: frontend::EventType, node_names: Vec<NodeName>, message: String) {
+        self.frontend_service
+            .send(frontend::Message::SendEvent(event_type, node_names, message))
+            .expect("Should success send event");
+    }
 }
 
 impl db::EventSubscriber for EventPropagator {
@@ -70,21 +77,26 @@ impl db::EventSubscriber for EventPropagator {
                     }
                 }
 
+                let node_names = vec![after.name.clone()];
                 let message = jsonrpc::serialize_notification(
                     "dashboard_updated",
                     json!({
                         "nodes": [diff.clone()]
                     }),
                 );
-
-                self.frontend_service.send(frontend::Message::SendEvent(message)).expect("Should success send event");
+                self.send_event(frontend::EventType::DashboardUpdated, node_names.clone(), message);
                 let message = jsonrpc::serialize_notification("node_updated", diff);
-                self.frontend_service.send(frontend::Message::SendEvent(message)).expect("Should success send event");
+                self.send_event(frontend::EventType::NodeUpdated, node_names, message);
             }
             db::Event::ConnectionChanged {
                 added,
                 removed,
             } => {
+                let node_names: Vec<NodeName> = added
+                    .iter()
+                    .chain(removed.iter())
+                    .flat_map(|(first, second)| vec![first.clone(), second.clone()])
+                    .collect();
                 let collection_added: Vec<Value> = added
                     .iter()
                     .map(|(first, second)| {
@@ -110,8 +122,7 @@ impl db::EventSubscriber for EventPropagator {
                         "connectionsRemoved": collection_removed,
                     }),
                 );
-
-                self.frontend_service.send(frontend::Message::SendEvent(message)).expect("Should success send event");
+                self.send_event(frontend::EventType::DashboardUpdated, node_names, message);
             }
             db::Event::ClientExtraUpdated {
                 name,
@@ -141,7 +152,29 @@ impl db::EventSubscriber for EventPropagator {
                 }
 
                 let message = jsonrpc::serialize_notification("node_updated", diff);
-                self.frontend_service.send(frontend::Message::SendEvent(message)).expect("Should success send event");
+                self.send_event(frontend::EventType::NodeUpdated, vec![name], message);
+            }
+            db::Event::LogsWritten {
+                name,
+                tags,
+                logs,
+            } => {
+                self.frontend_service
+                    .send(frontend::Message::SendLogs(name, tags, logs))
+                    .expect("Should success send logs");
+            }
+            db::Event::NodeTagsUpdated {
+                name,
+                tags,
+            } => {
+                let message = jsonrpc::serialize_notification(
+                    "node_updated",
+                    json!({
+                        "name": name,
+                        "tags": tags,
+                    }),
+                );
+                self.send_event(frontend::EventType::NodeUpdated, vec![name], message);
             }
         }
     }
@@ -0,0 +1,245 @@
+use super::client::client::UPDATE_INTERVAL;
+use super::client::{CodeChainRPC, ServiceSender as ClientServiceSender};
+use super::common_rpc_types::{NodeName, NodeStatus};
+use super::db::ServiceSender as DBServiceSender;
+use super::noti::Noti;
+use super::{frontend, jsonrpc};
+use primitives::H256;
+use serde_derive::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::thread;
+
+/// The nodes more than this number of blocks behind the highest best block are syncing and are not compared.
+const RECENT_BLOCKS: i64 = 100;
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkGroup {
+    pub hash: H256,
+    pub nodes: Vec<NodeName>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fork {
+    pub block_number: i64,
+    pub groups: Vec<ForkGroup>,
+}
+
+pub fn start(
+    noti: Arc<Noti>,
+    db_service: DBServiceSender,
+    client_service: ClientServiceSender,
+    frontend_service: frontend::ServiceSender,
+    network_id: String,
+) -> thread::JoinHandle<()> {
+    thread::Builder::new()
+        .name("fork detector".to_string())
+        .spawn(move || {
+            let mut detector = ForkDetector::default();
+            loop {
+                let fork = detector.detect(&db_service, &client_service);
+                if let Some(changed) = detector.update_reported_fork(fork) {
+                    report(&noti, &frontend_service, &network_id, changed);
+                }
+                thread::sleep(UPDATE_INTERVAL);
+            }
+        })
+        .expect("Should success running fork detector thread")
+}
+
+#[derive(Default)]
+struct ForkDetector {
+    block_hashes: HashMap<NodeName, BTreeMap<i64, H256>>,
+    reported_groups: Option<Vec<Vec<NodeName>>>,
+}
+
+impl ForkDetector {
+    /// Compares the block hashes of the running nodes at the highest block number that every one of them has,
+    /// skipping the nodes more than `RECENT_BLOCKS` behind.
+    fn detect(&mut self, db_service: &DBServiceSender, client_service: &ClientServiceSender) -> Option<Fork> {
+        let clients = match db_service.get_clients_state() {
+            Ok(clients) => clients,
+            Err(err) => {
+                cwarn!("Fork detector cannot get clients: {:?}", err);
+                return None
+            }
+        };
+        let best_blocks: Vec<_> = clients
+            .into_iter()
+            .filter(|client| client.status == NodeStatus::Run)
+            .filter_map(|client| client.best_block_id.map(|best_block_id| (client.name, best_block_id)))
+            .collect();
+
+        let best_numbers: Vec<_> =
+            best_blocks.iter().map(|(name, best_block_id)| (name.clone(), best_block_id.block_number)).collect();
+        let (block_number, compared) = comparison(&best_numbers)?;
+
+        self.block_hashes.retain(|name, _| compared.contains(name));
+        for (name, best_block_id) in &best_blocks {
+            if !compared.contains(name) {
+                continue
+            }
+            let hashes = self.block_hashes.entry(name.clone()).or_default();
+            hashes.insert(best_block_id.block_number, best_block_id.hash);
+            // The hash at the comparison height is kept, so it is not queried again on the next tick.
+            *hashes = hashes.split_off(&block_number);
+        }
+
+        let mut hashes = Vec::new();
+        for name in &compared {
+            let node_hashes = self.block_hashes.get_mut(name).expect("Inserted above");
+            if !node_hashes.contains_key(&block_number) {
+                match get_block_hash(client_service, name, block_number) {
+                    Ok(Some(hash)) => {
+                        node_hashes.insert(block_number, hash);
+                    }
+                    Ok(None) => continue,
+                    Err(err) => {
+                        cwarn!("Fork detector cannot get the block hash of {} at {}: {}", name, block_number, err);
+                        continue
+                    }
+                }
+            }
+            hashes.push((name.clone(), node_hashes[&block_number]));
+        }
+
+        find_fork(block_number, hashes)
+    }
+
+    /// Returns the fork status to report only when the diverging groups are changed,
+    /// so that a fork lasting for a while is reported once.
+    fn update_reported_fork(&mut self, fork: Option<Fork>) -> Option<Option<Fork>> {
+        let groups = fork.as_ref().map(|fork| fork.groups.iter().map(|group| group.nodes.clone()).collect::<Vec<_>>());
+        if groups == self.reported_groups {
+            return None
+        }
+        self.reported_groups = groups;
+        Some(fork)
+    }
+}
+
+/// Returns the highest block number that every node within `RECENT_BLOCKS` of the highest best block has, and
+/// those nodes. A node syncing from far behind doesn't hide a fork between the up-to-date nodes.
+fn comparison(best_numbers: &[(NodeName, i64)]) -> Option<(i64, Vec<NodeName>)> {
+    let highest = best_numbers.iter().map(|(_, number)| *number).max()?;
+    let compared: Vec<(NodeName, i64)> =
+        best_numbers.iter().filter(|(_, number)| highest - number <= RECENT_BLOCKS).cloned().collect();
+    let block_number = compared.iter().map(|(_, number)| *number).min()?;
+    Some((block_number, compared.into_iter().map(|(name, _)| name).collect()))
+}
+
+fn get_block_hash(client_service: &ClientServiceSender, name: &str, block_number: i64) -> Result<Option<H256>, String> {
+    let client = client_service.get_client(name).ok_or_else(|| "The client is disconnected".to_string())?;
+    CodeChainRPC::new(client).get_block_hash(NodeStatus::Run, block_number)
+}
+
+fn find_fork(block_number: i64, hashes: Vec<(NodeName, H256)>) -> Option<Fork> {
+    let mut groups: BTreeMap<H256, Vec<NodeName>> = BTreeMap::new();
+    for (name, hash) in hashes {
+        groups.entry(hash).or_default().push(name);
+    }
+    if groups.len() < 2 {
+        return None
+    }
+
+    let mut groups: Vec<ForkGroup> = groups
+        .into_iter()
+        .map(|(hash, mut nodes)| {
+            nodes.sort();
+            ForkGroup {
+                hash,
+                nodes,
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| a.nodes.cmp(&b.nodes));
+    Some(Fork {
+        block_number,
+        groups,
+    })
+}
+
+fn report(noti: &Noti, frontend_service: &frontend::ServiceSender, network_id: &str, fork: Option<Fork>) {
+    match &fork {
+        Some(fork) => {
+            let groups: Vec<String> =
+                fork.groups.iter().map(|group| format!("[{}] has {:?}", group.nodes.join(", "), group.hash)).collect();
+            let message = format!("The chain is forked at block {}. {}", fork.block_number, groups.join(" / "));
+            cerror!("{}", message);
+            noti.error(network_id, &message);
+        }
+        None => cinfo!("The chain fork is resolved"),
+    }
+
+    let message = jsonrpc::serialize_notification(
+        "dashboard_updated",
+        json!({
+            "fork": fork,
+        }),
+    );
+    frontend_service
+        .send(frontend::Message::SendEvent(frontend::EventType::DashboardUpdated, Vec::new(), message))
+        .expect("Should success send event");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_hashes_are_not_fork() {
+        let hash = H256::from([1; 32]);
+        assert_eq!(None, find_fork(10, vec![("a".to_string(), hash), ("b".to_string(), hash)]));
+    }
+
+    #[test]
+    fn group_nodes_by_hash() {
+        let hash1 = H256::from([1; 32]);
+        let hash2 = H256::from([2; 32]);
+        let fork =
+            find_fork(10, vec![("c".to_string(), hash1), ("b".to_string(), hash2), ("a".to_string(), hash1)]).unwrap();
+        assert_eq!(
+            vec![
+                ForkGroup {
+                    hash: hash1,
+                    nodes: vec!["a".to_string(), "c".to_string()],
+                },
+                ForkGroup {
+                    hash: hash2,
+                    nodes: vec!["b".to_string()],
+                },
+            ],
+            fork.groups
+        );
+    }
+
+    #[test]
+    fn lagging_nodes_are_not_compared() {
+        let best_numbers = vec![("a".to_string(), 1000), ("b".to_string(), 995), ("syncing".to_string(), 10)];
+        assert_eq!(Some((995, vec!["a".to_string(), "b".to_string()])), comparison(&best_numbers));
+
+        let best_numbers = vec![("a".to_string(), 1000), ("b".to_string(), 900)];
+        assert_eq!(Some((900, vec!["a".to_string(), "b".to_string()])), comparison(&best_numbers));
+
+        assert_eq!(None, comparison(&[]));
+    }
+
+    #[test]
+    fn report_only_changed_fork() {
+        let mut detector = ForkDetector::default();
+        let fork = |block_number| {
+            find_fork(block_number, vec![
+                ("a".to_string(), H256::from([1; 32])),
+                ("b".to_string(), H256::from([2; 32])),
+            ])
+        };
+
+        assert_eq!(None, detector.update_reported_fork(None));
+        assert!(detector.update_reported_fork(fork(10)).unwrap().is_some());
+        assert_eq!(None, detector.update_reported_fork(fork(11)));
+        assert_eq!(Some(None), detector.update_reported_fork(None));
+    }
+}
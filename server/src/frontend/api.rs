@@ -1,42 +1,242 @@
 use super::super::client::SendClientRPC;
-use super::super::common_rpc_types::{GraphCommonArgs, NodeName, ShellStartCodeChainRequest, UpdateCodeChainRequest};
+use super::super::common_rpc_types::{
+    GraphCommonArgs, GraphPeriod, Job, JobRun, JobSchedule, NodeName, NodeSelector, Rollout,
+    ShellStartCodeChainRequest, UpdateCodeChainRequest, UpdateRolloutRequest,
+};
+use super::super::db::{self, LogFilter, LogQueryParams};
+use super::super::node_control::has_any_tag;
 use super::super::router::Router;
-use super::super::rpc::{response, RPCError, RPCResponse};
+use super::super::rpc::{response, RPCError, RPCResponse, RPCResult};
+use super::super::scheduler;
+use super::log_export::{self, LogExportFormat};
+use super::service::Message as ServiceMessage;
 use super::types::{
-    Context, DashboardGetNetworkResponse, DashboardNode, GraphNetworkOutAllAVGResponse, GraphNetworkOutAllResponse,
-    GraphNetworkOutNodeExtensionResponse, GraphNetworkOutNodePeerResponse, LogGetRequest, LogGetResponse,
-    LogGetTargetsResponse, NodeConnection, NodeGetInfoResponse,
+    AuditGetResponse, AuthLoginResponse, Context, DashboardGetNetworkRequest, DashboardGetNetworkResponse,
+    DashboardNode, EventFilter, GraphBlockLagAllResponse, GraphHardwareAllResponse, GraphHardwareNodeResponse,
+    GraphNetworkOutAllAVGResponse, GraphNetworkOutAllResponse, GraphNetworkOutNodeExtensionResponse,
+    GraphNetworkOutNodePeerResponse, GraphPeerCountAllResponse, GraphPeerCountNodeResponse, JobCreateRequest,
+    JobGetHistoryRequest, LogGetHistogramResponse, LogGetRequest, LogGetResponse, LogGetTargetsResponse,
+    MaterializedViewGetStatusResponse, NodeConnection, NodeGetInfoResponse, NodeGetStatusHistoryResponse,
+    NodesControlResponse, RetentionGetStatusResponse,
 };
+use crate::auth::{self, Role};
+use chrono::{DateTime, Duration, Utc};
+use jsonrpc_core::types::{Failure, Output, Success};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Instant;
 
 pub fn add_routing(router: &mut Router<Context>) {
-    router.add_route("ping", Box::new(ping as fn(Context) -> RPCResponse<String>));
+    router.add_public_route(
+        "auth_login",
+        Box::new(auth_login as fn(Context, (String, String)) -> RPCResponse<AuthLoginResponse>),
+    );
+    router.add_public_route(
+        "auth_resume",
+        Box::new(auth_resume as fn(Context, (String,)) -> RPCResponse<AuthLoginResponse>),
+    );
+    router.add_route("auth_logout", Role::Viewer, Box::new(auth_logout as fn(Context) -> RPCResponse<()>));
+    router.add_route(
+        "audit_get",
+        Role::Operator,
+        Box::new(audit_get as fn(Context, (db::AuditQueryParams,)) -> RPCResponse<AuditGetResponse>),
+    );
+    router.add_public_route("ping", Box::new(ping as fn(Context) -> RPCResponse<String>));
     router.add_route(
         "node_getInfo",
+        Role::Viewer,
         Box::new(node_get_info as fn(Context, (String,)) -> RPCResponse<NodeGetInfoResponse>),
     );
     router.add_route(
         "dashboard_getNetwork",
-        Box::new(dashboard_get_network as fn(Context) -> RPCResponse<DashboardGetNetworkResponse>),
+        Role::Viewer,
+        Box::new(
+            dashboard_get_network
+                as fn(Context, DashboardGetNetworkRequest) -> RPCResponse<DashboardGetNetworkResponse>,
+        ),
     );
-    router.add_route(
+    router.add_mutating_route(
         "node_start",
+        Role::Operator,
         Box::new(node_start as fn(Context, (String, ShellStartCodeChainRequest)) -> RPCResponse<()>),
     );
-    router.add_route("node_stop", Box::new(node_stop as fn(Context, (String,)) -> RPCResponse<()>));
-    router.add_route(
+    router.add_mutating_route(
+        "node_stop",
+        Role::Operator,
+        Box::new(node_stop as fn(Context, (String,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
         "node_update",
+        Role::Operator,
         Box::new(node_update as fn(Context, (NodeName, UpdateCodeChainRequest)) -> RPCResponse<()>),
     );
-    router.add_route("log_getTargets", Box::new(log_get_targets as fn(Context) -> RPCResponse<LogGetTargetsResponse>));
-    router.add_route("log_get", Box::new(log_get as fn(Context, (LogGetRequest,)) -> RPCResponse<LogGetResponse>));
+    router.add_mutating_route(
+        "node_setTags",
+        Role::Operator,
+        Box::new(node_set_tags as fn(Context, (NodeName, Vec<String>)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_callRPC",
+        Role::Operator,
+        Box::new(node_call_rpc as fn(Context, (NodeName, String, Vec<Value>)) -> RPCResponse<Value>),
+    );
+    router.add_mutating_route(
+        "node_addToWhitelist",
+        Role::Operator,
+        Box::new(node_add_to_whitelist as fn(Context, (NodeName, String, Option<String>)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_removeFromWhitelist",
+        Role::Operator,
+        Box::new(node_remove_from_whitelist as fn(Context, (NodeName, String)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_enableWhitelist",
+        Role::Operator,
+        Box::new(node_enable_whitelist as fn(Context, (NodeName,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_disableWhitelist",
+        Role::Operator,
+        Box::new(node_disable_whitelist as fn(Context, (NodeName,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_addToBlacklist",
+        Role::Operator,
+        Box::new(node_add_to_blacklist as fn(Context, (NodeName, String, Option<String>)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_removeFromBlacklist",
+        Role::Operator,
+        Box::new(node_remove_from_blacklist as fn(Context, (NodeName, String)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_enableBlacklist",
+        Role::Operator,
+        Box::new(node_enable_blacklist as fn(Context, (NodeName,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "node_disableBlacklist",
+        Role::Operator,
+        Box::new(node_disable_blacklist as fn(Context, (NodeName,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "nodes_start",
+        Role::Operator,
+        Box::new(nodes_start as fn(Context, (NodeSelector,)) -> RPCResponse<NodesControlResponse>),
+    );
+    router.add_mutating_route(
+        "nodes_stop",
+        Role::Operator,
+        Box::new(nodes_stop as fn(Context, (NodeSelector,)) -> RPCResponse<NodesControlResponse>),
+    );
+    router.add_mutating_route(
+        "nodes_restart",
+        Role::Operator,
+        Box::new(nodes_restart as fn(Context, (NodeSelector,)) -> RPCResponse<NodesControlResponse>),
+    );
+    router.add_mutating_route(
+        "update_rollout",
+        Role::Operator,
+        Box::new(update_rollout as fn(Context, (UpdateRolloutRequest,)) -> RPCResponse<i64>),
+    );
+    router.add_mutating_route(
+        "update_rolloutResume",
+        Role::Operator,
+        Box::new(update_rollout_resume as fn(Context, (i64,)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "update_rolloutAbort",
+        Role::Operator,
+        Box::new(update_rollout_abort as fn(Context, (i64,)) -> RPCResponse<()>),
+    );
+    router.add_route(
+        "update_rolloutGet",
+        Role::Viewer,
+        Box::new(update_rollout_get as fn(Context, (i64,)) -> RPCResponse<Option<Rollout>>),
+    );
+    router.add_route(
+        "update_rolloutList",
+        Role::Viewer,
+        Box::new(update_rollout_list as fn(Context) -> RPCResponse<Vec<Rollout>>),
+    );
+    router.add_mutating_route(
+        "job_create",
+        Role::Operator,
+        Box::new(job_create as fn(Context, (JobCreateRequest,)) -> RPCResponse<i64>),
+    );
+    router.add_mutating_route(
+        "job_setEnabled",
+        Role::Operator,
+        Box::new(job_set_enabled as fn(Context, (i64, bool)) -> RPCResponse<()>),
+    );
+    router.add_mutating_route(
+        "job_delete",
+        Role::Operator,
+        Box::new(job_delete as fn(Context, (i64,)) -> RPCResponse<()>),
+    );
+    router.add_route("job_list", Role::Viewer, Box::new(job_list as fn(Context) -> RPCResponse<Vec<Job>>));
+    router.add_route(
+        "job_getHistory",
+        Role::Viewer,
+        Box::new(job_get_history as fn(Context, (JobGetHistoryRequest,)) -> RPCResponse<Vec<JobRun>>),
+    );
+    router.add_route(
+        "node_getStatusHistory",
+        Role::Viewer,
+        Box::new(
+            node_get_status_history
+                as fn(Context, (NodeName, DateTime<Utc>, DateTime<Utc>)) -> RPCResponse<NodeGetStatusHistoryResponse>,
+        ),
+    );
+    router.add_route(
+        "log_getTargets",
+        Role::Viewer,
+        Box::new(log_get_targets as fn(Context) -> RPCResponse<LogGetTargetsResponse>),
+    );
+    router.add_route(
+        "log_get",
+        Role::Viewer,
+        Box::new(log_get as fn(Context, (LogGetRequest,)) -> RPCResponse<LogGetResponse>),
+    );
+    router.add_route(
+        "event_subscribe",
+        Role::Viewer,
+        Box::new(event_subscribe as fn(Context, (EventFilter,)) -> RPCResponse<()>),
+    );
+    router.add_route(
+        "log_subscribe",
+        Role::Viewer,
+        Box::new(log_subscribe as fn(Context, (LogFilter,)) -> RPCResponse<()>),
+    );
+    router.add_route("log_unsubscribe", Role::Viewer, Box::new(log_unsubscribe as fn(Context) -> RPCResponse<()>));
+    router.add_route(
+        "log_getHistogram",
+        Role::Viewer,
+        Box::new(
+            log_get_histogram as fn(Context, (LogGetRequest, GraphPeriod)) -> RPCResponse<LogGetHistogramResponse>,
+        ),
+    );
+    router.add_route(
+        "log_export",
+        Role::Viewer,
+        Box::new(log_export as fn(Context, (String, LogQueryParams, LogExportFormat)) -> RPCResponse<()>),
+    );
+    router.add_route(
+        "log_ackExportChunk",
+        Role::Viewer,
+        Box::new(log_ack_export_chunk as fn(Context, (String, usize)) -> RPCResponse<()>),
+    );
     router.add_route(
         "graph_network_out_all_node",
+        Role::Viewer,
         Box::new(
             graph_network_out_all_node as fn(Context, (GraphCommonArgs,)) -> RPCResponse<GraphNetworkOutAllResponse>,
         ),
     );
     router.add_route(
         "graph_network_out_all_node_avg",
+        Role::Viewer,
         Box::new(
             graph_network_out_all_node_avg
                 as fn(Context, (GraphCommonArgs,)) -> RPCResponse<GraphNetworkOutAllAVGResponse>,
@@ -44,6 +244,7 @@ pub fn add_routing(router: &mut Router<Context>) {
     );
     router.add_route(
         "graph_network_out_node_extension",
+        Role::Viewer,
         Box::new(
             graph_network_out_node_extension
                 as fn(Context, (NodeName, GraphCommonArgs)) -> RPCResponse<GraphNetworkOutNodeExtensionResponse>,
@@ -51,20 +252,152 @@ pub fn add_routing(router: &mut Router<Context>) {
     );
     router.add_route(
         "graph_network_out_node_peer",
+        Role::Viewer,
         Box::new(
             graph_network_out_node_peer
                 as fn(Context, (NodeName, GraphCommonArgs)) -> RPCResponse<GraphNetworkOutNodePeerResponse>,
         ),
     );
+    router.add_route(
+        "graph_hardware_node",
+        Role::Viewer,
+        Box::new(
+            graph_hardware_node as fn(Context, (NodeName, GraphCommonArgs)) -> RPCResponse<GraphHardwareNodeResponse>,
+        ),
+    );
+    router.add_route(
+        "graph_hardware_all",
+        Role::Viewer,
+        Box::new(graph_hardware_all as fn(Context, (GraphCommonArgs,)) -> RPCResponse<GraphHardwareAllResponse>),
+    );
+    router.add_route(
+        "graph_block_lag_all",
+        Role::Viewer,
+        Box::new(graph_block_lag_all as fn(Context, (GraphCommonArgs,)) -> RPCResponse<GraphBlockLagAllResponse>),
+    );
+    router.add_route(
+        "graph_peer_count_node",
+        Role::Viewer,
+        Box::new(
+            graph_peer_count_node
+                as fn(Context, (NodeName, GraphCommonArgs)) -> RPCResponse<GraphPeerCountNodeResponse>,
+        ),
+    );
+    router.add_route(
+        "graph_peer_count_all",
+        Role::Viewer,
+        Box::new(graph_peer_count_all as fn(Context, (GraphCommonArgs,)) -> RPCResponse<GraphPeerCountAllResponse>),
+    );
+    router.add_route(
+        "retention_getStatus",
+        Role::Viewer,
+        Box::new(retention_get_status as fn(Context) -> RPCResponse<RetentionGetStatusResponse>),
+    );
+    router.add_route(
+        "materializedView_getStatus",
+        Role::Viewer,
+        Box::new(materialized_view_get_status as fn(Context) -> RPCResponse<MaterializedViewGetStatusResponse>),
+    );
+}
+
+/// Starts a session of the connection. The token resumes the session on another connection with `auth_resume`.
+/// An address is locked out for a while after failing to log in several times.
+fn auth_login(context: Context, args: (String, String)) -> RPCResponse<AuthLoginResponse> {
+    let (name, password) = args;
+    let address = context.peer_addr.map(|peer_addr| peer_addr.ip());
+    if context.login_limiter.is_locked_out(address, Instant::now()) {
+        cwarn!("A login from {:?} is rejected because of the failed logins", address);
+        return Err(RPCError::TooManyFailedLogins)
+    }
+    let verified = match context.db_service.get_user(name)? {
+        Some(user) => Some(user).filter(|user| auth::verify_password(&password, &user.password_hash)),
+        None => {
+            auth::verify_password_of_unknown_user(&password);
+            None
+        }
+    };
+    let user = match verified {
+        Some(user) => user,
+        None => {
+            context.login_limiter.fail(address, Instant::now());
+            return Err(RPCError::Unauthenticated)
+        }
+    };
+    context.login_limiter.succeed(address);
+    let session = db::Session {
+        token: auth::generate_token(),
+        user_name: user.name,
+        role: user.role,
+        expires_at: Utc::now() + Duration::seconds(context.auth_config.session_ttl as i64),
+    };
+    context.db_service.create_session(session.clone())?;
+    cinfo!("{} logged in as {}", session.user_name, session.role);
+    response(open_session(&context, session)?)
+}
+
+/// Resumes the session of the token returned by `auth_login`, e.g. after reconnecting.
+fn auth_resume(context: Context, args: (String,)) -> RPCResponse<AuthLoginResponse> {
+    let (token,) = args;
+    let session = context.db_service.get_session(token)?.ok_or(RPCError::Unauthenticated)?;
+    response(open_session(&context, session)?)
+}
+
+/// Binds the session to the connection, which starts receiving the events.
+fn open_session(context: &Context, session: db::Session) -> RPCResult<AuthLoginResponse> {
+    let result = AuthLoginResponse {
+        token: session.token.clone(),
+        role: session.role,
+        expires_at: session.expires_at,
+    };
+    let previous = context.session.write().replace(session);
+    if previous.is_none() {
+        context
+            .frontend_service
+            .send(ServiceMessage::AddWS(context.out.clone()))
+            .map_err(|err| RPCError::Internal(err.to_string()))?;
+    }
+    Ok(result)
+}
+
+fn auth_logout(context: Context) -> RPCResponse<()> {
+    let session = context.session.write().take();
+    if let Some(session) = session {
+        context.db_service.delete_session(session.token)?;
+        context
+            .frontend_service
+            .send(ServiceMessage::RemoveWS(context.out.clone()))
+            .map_err(|err| RPCError::Internal(err.to_string()))?;
+    }
+    response(())
+}
+
+fn audit_get(context: Context, args: (db::AuditQueryParams,)) -> RPCResponse<AuditGetResponse> {
+    let (params,) = args;
+    let rows = context.db_service.get_audit_logs(params)?;
+    response(AuditGetResponse {
+        rows,
+    })
 }
 
 fn ping(_: Context) -> RPCResponse<String> {
     response("pong".to_string())
 }
 
-fn dashboard_get_network(context: Context) -> RPCResponse<DashboardGetNetworkResponse> {
-    let clients_state = context.db_service.get_clients_state()?;
-    let connections = context.db_service.get_connections()?;
+/// Returns the nodes having any of the tags and the connections between them. Empty tags select every node.
+fn dashboard_get_network(
+    context: Context,
+    req: DashboardGetNetworkRequest,
+) -> RPCResponse<DashboardGetNetworkResponse> {
+    let tags = req.tags;
+    let mut clients_state = context.db_service.get_clients_state()?;
+    let mut connections = context.db_service.get_connections()?;
+    if !tags.is_empty() {
+        let node_tags = context.db_service.get_node_tags()?;
+        clients_state.retain(|client| has_any_tag(&node_tags, &client.name, &tags));
+        connections.retain(|(node_a, node_b)| {
+            has_any_tag(&node_tags, node_a, &tags) && has_any_tag(&node_tags, node_b, &tags)
+        });
+    }
     let dashboard_nodes = clients_state.iter().map(|client| DashboardNode::from_db_state(client)).collect();
     response(DashboardGetNetworkResponse {
         nodes: dashboard_nodes,
@@ -75,8 +408,20 @@ fn dashboard_get_network(context: Context) -> RPCResponse<DashboardGetNetworkRes
 fn node_get_info(context: Context, args: (String,)) -> RPCResponse<NodeGetInfoResponse> {
     let (name,) = args;
     let client_query_result = context.db_service.get_client_query_result(&name)?.ok_or(RPCError::ClientNotFound)?;
-    let extra = context.db_service.get_client_extra(name)?;
-    response(NodeGetInfoResponse::from_db_state(&client_query_result, &extra))
+    let extra = context.db_service.get_client_extra(name.clone())?;
+    let mut info = NodeGetInfoResponse::from_db_state(&client_query_result, &extra);
+    info.tags = context.db_service.get_tags_of_node(name)?;
+    response(info)
+}
+
+/// Replaces the tags of the node. The tags select the nodes in the batch RPCs, the graphs, the logs and the alerts.
+fn node_set_tags(context: Context, args: (NodeName, Vec<String>)) -> RPCResponse<()> {
+    let (name, tags) = args;
+    if tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err(RPCError::InvalidParams("A tag cannot be empty".to_string()))
+    }
+    context.db_service.set_node_tags(name, tags)?;
+    response(())
 }
 
 fn node_start(context: Context, args: (NodeName, ShellStartCodeChainRequest)) -> RPCResponse<()> {
@@ -96,35 +441,254 @@ fn node_start(context: Context, args: (NodeName, ShellStartCodeChainRequest)) ->
 
 fn node_stop(context: Context, args: (String,)) -> RPCResponse<()> {
     let (name,) = args;
+    context.node_control().stop(&name)?;
+    response(())
+}
 
-    let client = context.client_service.get_client(&name);
-    if client.is_none() {
-        return Err(RPCError::ClientNotFound)
+/// Calls a CodeChain method allowed by `node_rpc.allowed_methods` and returns the JSON-RPC response of CodeChain.
+fn node_call_rpc(context: Context, args: (NodeName, String, Vec<Value>)) -> RPCResponse<Value> {
+    let (name, method, params) = args;
+    if !context.node_rpc_config.allows(&method) {
+        return Err(RPCError::PermissionDenied)
     }
-    let client = client.expect("Already checked");
-    client.shell_stop_codechain()?;
+    let client = context.client_service.get_client(&name).ok_or(RPCError::ClientNotFound)?;
+    let result = client.codechain_call_rpc_raw((method, params))?;
+    response(result.inner_response)
+}
+
+fn node_add_to_whitelist(context: Context, args: (NodeName, String, Option<String>)) -> RPCResponse<()> {
+    let (name, address, tag) = args;
+    check_network_address(&address)?;
+    call_codechain(&context, &name, "net_addToWhitelist", vec![
+        Value::from(address),
+        tag.map_or(Value::Null, Value::from),
+    ])?;
+    response(())
+}
+
+fn node_remove_from_whitelist(context: Context, args: (NodeName, String)) -> RPCResponse<()> {
+    let (name, address) = args;
+    check_network_address(&address)?;
+    call_codechain(&context, &name, "net_removeFromWhitelist", vec![Value::from(address)])?;
+    response(())
+}
+
+fn node_enable_whitelist(context: Context, args: (NodeName,)) -> RPCResponse<()> {
+    let (name,) = args;
+    call_codechain(&context, &name, "net_enableWhitelist", Vec::new())?;
+    response(())
+}
+
+fn node_disable_whitelist(context: Context, args: (NodeName,)) -> RPCResponse<()> {
+    let (name,) = args;
+    call_codechain(&context, &name, "net_disableWhitelist", Vec::new())?;
+    response(())
+}
+
+fn node_add_to_blacklist(context: Context, args: (NodeName, String, Option<String>)) -> RPCResponse<()> {
+    let (name, address, tag) = args;
+    check_network_address(&address)?;
+    call_codechain(&context, &name, "net_addToBlacklist", vec![
+        Value::from(address),
+        tag.map_or(Value::Null, Value::from),
+    ])?;
+    response(())
+}
+
+fn node_remove_from_blacklist(context: Context, args: (NodeName, String)) -> RPCResponse<()> {
+    let (name, address) = args;
+    check_network_address(&address)?;
+    call_codechain(&context, &name, "net_removeFromBlacklist", vec![Value::from(address)])?;
+    response(())
+}
+
+fn node_enable_blacklist(context: Context, args: (NodeName,)) -> RPCResponse<()> {
+    let (name,) = args;
+    call_codechain(&context, &name, "net_enableBlacklist", Vec::new())?;
+    response(())
+}
 
+fn node_disable_blacklist(context: Context, args: (NodeName,)) -> RPCResponse<()> {
+    let (name,) = args;
+    call_codechain(&context, &name, "net_disableBlacklist", Vec::new())?;
     response(())
 }
 
+/// The whitelist and the blacklist take an IP address or a CIDR block, e.g. `10.0.0.0/8`.
+fn check_network_address(address: &str) -> RPCResult<()> {
+    let invalid = || RPCError::InvalidParams(format!("{} is not an IP address or a CIDR block", address));
+    let mut parts = address.splitn(2, '/');
+    let ip: IpAddr = parts.next().unwrap_or_default().parse().map_err(|_| invalid())?;
+    if let Some(prefix) = parts.next() {
+        let prefix: u8 = prefix.parse().map_err(|_| invalid())?;
+        let max_prefix = if ip.is_ipv4() {
+            32
+        } else {
+            128
+        };
+        if prefix > max_prefix {
+            return Err(invalid())
+        }
+    }
+    Ok(())
+}
+
+/// Calls a CodeChain method of the node, failing with the error of CodeChain.
+/// The changed lists are sent as `node_updated` notifications after the next update of the node.
+fn call_codechain(context: &Context, name: &str, method: &str, params: Vec<Value>) -> RPCResult<Value> {
+    let client = context.client_service.get_client(name).ok_or(RPCError::ClientNotFound)?;
+    match client.codechain_call_rpc((method.to_string(), params))? {
+        Output::Success(Success {
+            result,
+            ..
+        }) => Ok(result),
+        Output::Failure(Failure {
+            error,
+            ..
+        }) => Err(RPCError::FromClient(error)),
+    }
+}
+
+/// Starts the selected nodes with the environment and the arguments that each node was started with.
+fn nodes_start(context: Context, args: (NodeSelector,)) -> RPCResponse<NodesControlResponse> {
+    let (selector,) = args;
+    let node_control = context.node_control();
+    let names = node_control.select(selector)?;
+    response(NodesControlResponse {
+        results: node_control.run(names, |node_control, name| node_control.start(name)),
+    })
+}
+
+fn nodes_stop(context: Context, args: (NodeSelector,)) -> RPCResponse<NodesControlResponse> {
+    let (selector,) = args;
+    let node_control = context.node_control();
+    let names = node_control.select(selector)?;
+    response(NodesControlResponse {
+        results: node_control.run(names, |node_control, name| node_control.stop(name)),
+    })
+}
+
+fn nodes_restart(context: Context, args: (NodeSelector,)) -> RPCResponse<NodesControlResponse> {
+    let (selector,) = args;
+    let node_control = context.node_control();
+    let names = node_control.select(selector)?;
+    response(NodesControlResponse {
+        results: node_control.run(names, |node_control, name| node_control.restart(name)),
+    })
+}
+
 fn node_update(context: Context, args: (NodeName, UpdateCodeChainRequest)) -> RPCResponse<()> {
     let (name, req) = args;
+    context.node_control().update(&name, req)?;
+    response(())
+}
 
-    let client = context.client_service.get_client(&name).ok_or(RPCError::ClientNotFound)?;
+/// Updates the nodes in waves of `batchSize` nodes. The progress is sent as `rollout_updated` notifications.
+fn update_rollout(context: Context, args: (UpdateRolloutRequest,)) -> RPCResponse<i64> {
+    let (mut req,) = args;
+    req.node_names = context.node_control().select(NodeSelector {
+        node_names: req.node_names.clone(),
+        tags: req.tags.clone(),
+        all: false,
+    })?;
+    let id = context.rollout_service.start(req, context.user_name())?;
+    response(id)
+}
 
-    let extra = context.db_service.get_client_extra(name)?;
-    let (env, args) = extra.map(|extra| (extra.prev_env, extra.prev_args)).unwrap_or_default();
-    client.shell_update_codechain((
-        ShellStartCodeChainRequest {
-            env,
-            args,
-        },
-        req,
-    ))?;
+fn update_rollout_resume(context: Context, args: (i64,)) -> RPCResponse<()> {
+    let (id,) = args;
+    context.rollout_service.resume(id)?;
+    response(())
+}
 
+fn update_rollout_abort(context: Context, args: (i64,)) -> RPCResponse<()> {
+    let (id,) = args;
+    context.rollout_service.abort(id)?;
     response(())
 }
 
+fn update_rollout_get(context: Context, args: (i64,)) -> RPCResponse<Option<Rollout>> {
+    let (id,) = args;
+    let rollout = context.db_service.get_rollout(id)?;
+    response(rollout)
+}
+
+fn update_rollout_list(context: Context) -> RPCResponse<Vec<Rollout>> {
+    let rollouts = context.db_service.get_rollouts()?;
+    response(rollouts)
+}
+
+/// Registers a node action that the scheduler runs at the time of the schedule.
+fn job_create(context: Context, args: (JobCreateRequest,)) -> RPCResponse<i64> {
+    let (req,) = args;
+    if req.name.trim().is_empty() {
+        return Err(RPCError::InvalidParams("The name of a job cannot be empty".to_string()))
+    }
+    if req.nodes.node_names.is_empty() && req.nodes.tags.is_empty() && !req.nodes.all {
+        return Err(RPCError::InvalidParams("No node is selected".to_string()))
+    }
+    let now = Utc::now();
+    let next_run_at = first_run_at(&req.schedule, now)?;
+    let id = context.db_service.create_job(Job {
+        id: 0,
+        name: req.name,
+        user_name: context.user_name(),
+        nodes: req.nodes,
+        action: req.action,
+        schedule: req.schedule,
+        enabled: true,
+        next_run_at: Some(next_run_at),
+        created_at: now,
+    })?;
+    response(id)
+}
+
+fn job_set_enabled(context: Context, args: (i64, bool)) -> RPCResponse<()> {
+    let (id, enabled) = args;
+    let mut job = context.db_service.get_job(id)?.ok_or_else(|| RPCError::InvalidParams(format!("No job {}", id)))?;
+    if enabled && !job.enabled {
+        job.next_run_at = Some(first_run_at(&job.schedule, Utc::now())?);
+    }
+    job.enabled = enabled;
+    context.db_service.update_job(job)?;
+    response(())
+}
+
+fn job_delete(context: Context, args: (i64,)) -> RPCResponse<()> {
+    let (id,) = args;
+    if !context.db_service.delete_job(id)? {
+        return Err(RPCError::InvalidParams(format!("No job {}", id)))
+    }
+    response(())
+}
+
+fn job_list(context: Context) -> RPCResponse<Vec<Job>> {
+    let jobs = context.db_service.get_jobs()?;
+    response(jobs)
+}
+
+fn job_get_history(context: Context, args: (JobGetHistoryRequest,)) -> RPCResponse<Vec<JobRun>> {
+    let (req,) = args;
+    let runs = context.db_service.get_job_runs(req.job_id)?;
+    response(runs)
+}
+
+fn first_run_at(schedule: &JobSchedule, now: DateTime<Utc>) -> RPCResult<DateTime<Utc>> {
+    scheduler::next_run_at(schedule, now)
+        .map_err(RPCError::InvalidParams)?
+        .ok_or_else(|| RPCError::InvalidParams("The schedule has no time in the future".to_string()))
+}
+
+fn node_get_status_history(
+    context: Context,
+    args: (NodeName, DateTime<Utc>, DateTime<Utc>),
+) -> RPCResponse<NodeGetStatusHistoryResponse> {
+    let (name, from, to) = args;
+
+    let history = context.db_service.get_node_status_history(name, from, to)?;
+    response(NodeGetStatusHistoryResponse::from_history(history, to))
+}
+
 fn log_get_targets(context: Context) -> RPCResponse<LogGetTargetsResponse> {
     let targets = context.db_service.get_log_targets()?;
     response(LogGetTargetsResponse {
@@ -140,6 +704,57 @@ fn log_get(context: Context, args: (LogGetRequest,)) -> RPCResponse<LogGetRespon
     })
 }
 
+/// Replaces the filter of the `dashboard_updated` and `node_updated` notifications sent to this connection.
+fn event_subscribe(context: Context, args: (EventFilter,)) -> RPCResponse<()> {
+    let (filter,) = args;
+    context
+        .frontend_service
+        .send(ServiceMessage::SetEventFilter(context.out.clone(), filter))
+        .map_err(|err| RPCError::Internal(err.to_string()))?;
+    response(())
+}
+
+/// The new logs matching the filter are sent as `log_appended` notifications until `log_unsubscribe` is called.
+fn log_subscribe(context: Context, args: (LogFilter,)) -> RPCResponse<()> {
+    let (filter,) = args;
+    context
+        .frontend_service
+        .send(ServiceMessage::SubscribeLogs(context.out.clone(), filter))
+        .map_err(|err| RPCError::Internal(err.to_string()))?;
+    response(())
+}
+
+fn log_unsubscribe(context: Context) -> RPCResponse<()> {
+    context
+        .frontend_service
+        .send(ServiceMessage::UnsubscribeLogs(context.out.clone()))
+        .map_err(|err| RPCError::Internal(err.to_string()))?;
+    response(())
+}
+
+fn log_get_histogram(context: Context, args: (LogGetRequest, GraphPeriod)) -> RPCResponse<LogGetHistogramResponse> {
+    let (req, period) = args;
+    let rows = context.db_service.get_log_histogram(req, period)?;
+    response(LogGetHistogramResponse {
+        rows,
+    })
+}
+
+/// The logs are sent as `log_exportChunk` notifications with the `id` given by the caller.
+/// The caller acknowledges the chunks with `log_ackExportChunk` to receive the next ones.
+fn log_export(context: Context, args: (String, LogQueryParams, LogExportFormat)) -> RPCResponse<()> {
+    let (id, params, format) = args;
+    log_export::start(&context.log_exports, context.db_service.clone(), context.out.clone(), id, params, format)?;
+    response(())
+}
+
+/// Acknowledges the chunks of the export up to `index`.
+fn log_ack_export_chunk(context: Context, args: (String, usize)) -> RPCResponse<()> {
+    let (id, index) = args;
+    context.log_exports.acknowledge(context.out.connection_id(), id, index);
+    response(())
+}
+
 fn graph_network_out_all_node(context: Context, args: (GraphCommonArgs,)) -> RPCResponse<GraphNetworkOutAllResponse> {
     let (graph_args,) = args;
 
@@ -184,3 +799,103 @@ fn graph_network_out_node_peer(
         rows,
     })
 }
+
+fn graph_hardware_node(context: Context, args: (NodeName, GraphCommonArgs)) -> RPCResponse<GraphHardwareNodeResponse> {
+    let (node_name, graph_args) = args;
+
+    let rows = context.db_service.get_hardware_node_graph(node_name, graph_args)?;
+    response(GraphHardwareNodeResponse {
+        rows,
+    })
+}
+
+fn graph_hardware_all(context: Context, args: (GraphCommonArgs,)) -> RPCResponse<GraphHardwareAllResponse> {
+    let (graph_args,) = args;
+
+    let rows = context.db_service.get_hardware_all_graph(graph_args)?;
+    response(GraphHardwareAllResponse {
+        rows,
+    })
+}
+
+fn graph_block_lag_all(context: Context, args: (GraphCommonArgs,)) -> RPCResponse<GraphBlockLagAllResponse> {
+    let (graph_args,) = args;
+
+    let rows = context.db_service.get_block_lag_all_graph(graph_args)?;
+    response(GraphBlockLagAllResponse {
+        rows,
+    })
+}
+
+fn graph_peer_count_node(
+    context: Context,
+    args: (NodeName, GraphCommonArgs),
+) -> RPCResponse<GraphPeerCountNodeResponse> {
+    let (node_name, graph_args) = args;
+
+    let rows = context.db_service.get_peer_count_node_graph(node_name, graph_args)?;
+    response(GraphPeerCountNodeResponse {
+        rows,
+    })
+}
+
+fn graph_peer_count_all(context: Context, args: (GraphCommonArgs,)) -> RPCResponse<GraphPeerCountAllResponse> {
+    let (graph_args,) = args;
+
+    let rows = context.db_service.get_peer_count_all_graph(graph_args)?;
+    response(GraphPeerCountAllResponse {
+        rows,
+    })
+}
+
+fn retention_get_status(context: Context) -> RPCResponse<RetentionGetStatusResponse> {
+    let tables = context.db_service.get_table_stats()?;
+    response(RetentionGetStatusResponse {
+        config: context.retention_config.clone(),
+        tables,
+    })
+}
+
+fn materialized_view_get_status(context: Context) -> RPCResponse<MaterializedViewGetStatusResponse> {
+    let views = context.db_service.get_materialized_view_refreshes()?;
+    response(MaterializedViewGetStatusResponse {
+        views,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_ip_addresses_and_cidr_blocks() {
+        assert!(check_network_address("10.0.0.1").is_ok());
+        assert!(check_network_address("10.0.0.0/8").is_ok());
+        assert!(check_network_address("::1").is_ok());
+        assert!(check_network_address("fe80::/10").is_ok());
+
+        assert!(matches!(check_network_address(""), Err(RPCError::InvalidParams(_))));
+        assert!(matches!(check_network_address("example.com"), Err(RPCError::InvalidParams(_))));
+        assert!(matches!(check_network_address("10.0.0.0/33"), Err(RPCError::InvalidParams(_))));
+        assert!(matches!(check_network_address("fe80::/129"), Err(RPCError::InvalidParams(_))));
+        assert!(matches!(check_network_address("10.0.0.0/"), Err(RPCError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn parse_the_params_of_list_routes() {
+        let (name, address, tag): (NodeName, String, Option<String>) =
+            serde_json::from_value(serde_json::json!(["node", "10.0.0.0/8", null])).unwrap();
+        assert_eq!(("node", "10.0.0.0/8", None), (name.as_str(), address.as_str(), tag));
+
+        let (_, _, tag): (NodeName, String, Option<String>) =
+            serde_json::from_value(serde_json::json!(["node", "10.0.0.1", "office"])).unwrap();
+        assert_eq!(Some("office".to_string()), tag);
+
+        let (name, address): (NodeName, String) =
+            serde_json::from_value(serde_json::json!(["node", "10.0.0.1"])).unwrap();
+        assert_eq!(("node", "10.0.0.1"), (name.as_str(), address.as_str()));
+
+        let (name,): (NodeName,) = serde_json::from_value(serde_json::json!(["node"])).unwrap();
+        assert_eq!("node", name);
+    }
+}
@@ -1,51 +1,51 @@
-use super::super::jsonrpc;
 use super::super::router::Router;
+use super::super::{jsonrpc, tls};
 use super::types::Context;
-use std::error::Error;
-use std::fmt;
+use openssl::ssl::{SslAcceptor, SslStream};
+use serde_json::Value;
 use std::sync::Arc;
-use ws::{self, CloseCode, Error as WSError, ErrorKind, Handler, Handshake, Result, Sender};
-
-#[derive(Debug)]
-struct CustomError {}
-
-impl Error for CustomError {}
-
-impl fmt::Display for CustomError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Custom error")
-    }
-}
+use ws::util::TcpStream;
+use ws::{self, CloseCode, Error as WSError, Handler, Handshake, Result, Sender};
 
 pub struct WebSocketHandler {
     pub out: Sender,
     pub context: Context,
     pub router: Arc<Router<Context>>,
     pub frontend_service: super::ServiceSender,
+    pub ssl: Option<Arc<SslAcceptor>>,
 }
 
 impl Handler for WebSocketHandler {
-    fn on_open(&mut self, handshake: Handshake) -> Result<()> {
-        if format!("/{}", self.context.passphrase) != handshake.request.resource() {
-            return Err(WSError::new(ErrorKind::Custom(Box::new(CustomError {})), "Authorization Error"))
-        }
+    fn upgrade_ssl_server(&mut self, sock: TcpStream) -> Result<SslStream<TcpStream>> {
+        tls::upgrade(self.ssl.as_deref(), sock)
+    }
 
-        self.frontend_service
-            .send(super::Message::AddWS(self.out.clone()))
-            .expect("Should success adding ws to frontend_service");
+    /// A connection should call `auth_login`, or `auth_resume` with a token, before the other RPCs.
+    /// The token is not read from the URL, which is written to the logs of the proxies.
+    /// The events are sent only to the connections that have a session.
+    fn on_open(&mut self, handshake: Handshake) -> Result<()> {
+        self.context.peer_addr = handshake.peer_addr;
         Ok(())
     }
 
     fn on_message(&mut self, msg: ws::Message) -> Result<()> {
+        let mut is_auth = false;
         let response: Option<String> = match msg {
             ws::Message::Text(text) => {
-                cinfo!("Receive {}", text);
+                is_auth = is_auth_request(&text);
+                if is_auth {
+                    cinfo!("Receive an auth request");
+                } else {
+                    cinfo!("Receive {}", text);
+                }
                 jsonrpc::handle(|method, arg| self.router.run(self.context.clone(), &method, arg), text)
             }
             _ => Some(jsonrpc::invalid_format()),
         };
 
-        cinfo!("Response {:?}", response);
+        if !is_auth {
+            cinfo!("Response {:?}", response);
+        }
         if let Some(response) = response {
             self.out.send(ws::Message::Text(response))
         } else {
@@ -60,9 +60,11 @@ impl Handler for WebSocketHandler {
             CloseCode::Abnormal => cinfo!("Closing handshake failed! Unable to obtain closing status from client."),
             _ => cinfo!("The client encountered an error: {}", reason),
         }
-        self.frontend_service
-            .send(super::Message::RemoveWS(self.out.clone()))
-            .expect("Should success remove ws from frontend_service");
+        if self.context.session.read().is_some() {
+            self.frontend_service
+                .send(super::Message::RemoveWS(self.out.clone()))
+                .expect("Should success remove ws from frontend_service");
+        }
     }
 
     fn on_error(&mut self, err: WSError) {
@@ -72,3 +74,10 @@ impl Handler for WebSocketHandler {
         cerror!("The server encountered an error: {:?}", err);
     }
 }
+
+fn is_auth_request(text: &str) -> bool {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|request| request["method"].as_str().map(jsonrpc::is_auth_method))
+        .unwrap_or(false)
+}
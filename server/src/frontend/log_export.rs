@@ -0,0 +1,291 @@
+use super::super::db::{self, Log, LogQueryParams};
+use super::super::jsonrpc;
+use super::super::rpc::{RPCError, RPCResult};
+use parking_lot::Mutex;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const CHUNK_SIZE: i64 = 1000;
+const MAX_EXPORTS_PER_CONNECTION: usize = 2;
+const MAX_EXPORTS: usize = 8;
+/// The number of chunks sent ahead of the acknowledgements
+const ACK_WINDOW: usize = 4;
+const ACK_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum LogExportFormat {
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogExportChunk {
+    id: String,
+    index: usize,
+    data: String,
+    done: bool,
+    error: Option<String>,
+}
+
+type ExportKey = (u32, String);
+
+/// The running exports, keyed by the connection and the id given by the caller.
+/// They are limited per connection and in total, and receive the acknowledgements of their chunks.
+#[derive(Default)]
+pub struct LogExports {
+    running: Mutex<HashMap<ExportKey, Sender<usize>>>,
+}
+
+impl LogExports {
+    fn register(self: &Arc<Self>, key: ExportKey) -> RPCResult<(Registration, Receiver<usize>)> {
+        let mut running = self.running.lock();
+        if running.contains_key(&key) {
+            return Err(RPCError::InvalidParams(format!("The log export {} is already running", key.1)))
+        }
+        let of_connection = running.keys().filter(|(connection_id, _)| *connection_id == key.0).count();
+        if of_connection >= MAX_EXPORTS_PER_CONNECTION || running.len() >= MAX_EXPORTS {
+            return Err(RPCError::TooManyLogExports)
+        }
+        let (tx, rx) = channel();
+        running.insert(key.clone(), tx);
+        Ok((
+            Registration {
+                exports: Arc::clone(self),
+                key,
+            },
+            rx,
+        ))
+    }
+
+    /// Lets the export send the chunks after `index`. Returns false if the export is not running.
+    pub fn acknowledge(&self, connection_id: u32, id: String, index: usize) -> bool {
+        let running = self.running.lock();
+        match running.get(&(connection_id, id)) {
+            Some(tx) => tx.send(index).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Removes the export from `LogExports` when the export thread ends.
+struct Registration {
+    exports: Arc<LogExports>,
+    key: ExportKey,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.exports.running.lock().remove(&self.key);
+    }
+}
+
+/// Sends every log matching `params` to `out` as `log_exportChunk` notifications of `CHUNK_SIZE` logs.
+/// At most `ACK_WINDOW` chunks are sent ahead of the acknowledgements by `log_ackExportChunk`.
+/// The last notification has `done` set, or `error` if the export failed or is aborted.
+pub fn start(
+    exports: &Arc<LogExports>,
+    db_service: db::ServiceSender,
+    out: ws::Sender,
+    id: String,
+    params: LogQueryParams,
+    format: LogExportFormat,
+) -> RPCResult<()> {
+    let (registration, acknowledgements) = exports.register((out.connection_id(), id.clone()))?;
+    thread::Builder::new()
+        .name("log export".to_string())
+        .spawn(move || {
+            let _registration = registration;
+            let mut after = None;
+            let mut index = 0;
+            let mut acknowledged = None;
+            loop {
+                if index >= ACK_WINDOW {
+                    if let Err(err) = wait_acknowledgement(&acknowledgements, &mut acknowledged, index - ACK_WINDOW) {
+                        cwarn!("Stop the log export {}: {}", id, err);
+                        send_chunk(&out, &id, index, String::new(), true, Some(err)).ok();
+                        return
+                    }
+                }
+                let (data, done, error) = match db_service.get_logs_after(params.clone(), after, CHUNK_SIZE) {
+                    Ok(logs) => {
+                        after = logs.last().map(|log| (log.timestamp, log.id));
+                        let header = index == 0
+                            && match format {
+                                LogExportFormat::Csv => true,
+                                LogExportFormat::Ndjson => false,
+                            };
+                        (serialize(&logs, format, header), (logs.len() as i64) < CHUNK_SIZE, None)
+                    }
+                    Err(err) => (String::new(), true, Some(err.to_string())),
+                };
+                if let Err(err) = send_chunk(&out, &id, index, data, done, error) {
+                    cwarn!("Stop the log export {}: {}", id, err);
+                    send_chunk(&out, &id, index + 1, String::new(), true, Some(err.to_string())).ok();
+                    return
+                }
+                if done {
+                    cinfo!("The log export {} is finished", id);
+                    return
+                }
+                index += 1;
+            }
+        })
+        .expect("Should success running log export thread");
+    Ok(())
+}
+
+/// Waits until the chunk of `index` is acknowledged.
+fn wait_acknowledgement(
+    acknowledgements: &Receiver<usize>,
+    acknowledged: &mut Option<usize>,
+    index: usize,
+) -> Result<(), String> {
+    while acknowledged.map_or(true, |acknowledged| acknowledged < index) {
+        match acknowledgements.recv_timeout(ACK_TIMEOUT) {
+            Ok(received) => *acknowledged = Some(acknowledged.map_or(received, |before| before.max(received))),
+            Err(RecvTimeoutError::Timeout) => {
+                return Err(format!("The chunk {} is not acknowledged in {} seconds", index, ACK_TIMEOUT.as_secs()))
+            }
+            Err(RecvTimeoutError::Disconnected) => return Err("The export is unregistered".to_string()),
+        }
+    }
+    Ok(())
+}
+
+fn send_chunk(
+    out: &ws::Sender,
+    id: &str,
+    index: usize,
+    data: String,
+    done: bool,
+    error: Option<String>,
+) -> ws::Result<()> {
+    out.send(jsonrpc::serialize_notification("log_exportChunk", LogExportChunk {
+        id: id.to_string(),
+        index,
+        data,
+        done,
+        error,
+    }))
+}
+
+fn serialize(logs: &[Log], format: LogExportFormat, header: bool) -> String {
+    let mut result = String::new();
+    match format {
+        LogExportFormat::Csv => {
+            if header {
+                result.push_str("id,nodeName,level,target,timestamp,message\n");
+            }
+            for log in logs {
+                let fields = [
+                    log.id.to_string(),
+                    csv_field(&log.node_name),
+                    csv_field(&log.level),
+                    csv_field(&log.target),
+                    log.timestamp.to_rfc3339(),
+                    csv_field(&log.message),
+                ];
+                result.push_str(&fields.join(","));
+                result.push('\n');
+            }
+        }
+        LogExportFormat::Ndjson => {
+            for log in logs {
+                result.push_str(&serde_json::to_string(log).expect("Should success serialization"));
+                result.push('\n');
+            }
+        }
+    }
+    result
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn log(message: &str) -> Log {
+        Log {
+            id: 1,
+            node_name: "node".to_string(),
+            level: "INFO".to_string(),
+            target: "miner".to_string(),
+            timestamp: chrono::Local.timestamp(0, 0),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn escape_csv_fields() {
+        assert_eq!("plain", csv_field("plain"));
+        assert_eq!("\"a, b\"", csv_field("a, b"));
+        assert_eq!("\"say \"\"hi\"\"\"", csv_field("say \"hi\""));
+        assert_eq!("\"two\nlines\"", csv_field("two\nlines"));
+    }
+
+    #[test]
+    fn serialize_csv_with_header() {
+        let csv = serialize(&[log("a, b")], LogExportFormat::Csv, true);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!("id,nodeName,level,target,timestamp,message", lines[0]);
+        assert!(lines[1].starts_with("1,node,INFO,miner,"));
+        assert!(lines[1].ends_with(",\"a, b\""));
+    }
+
+    #[test]
+    fn serialize_ndjson() {
+        let ndjson = serialize(&[log("a"), log("b")], LogExportFormat::Ndjson, false);
+        let lines: Vec<serde_json::Value> = ndjson.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(2, lines.len());
+        assert_eq!("a", lines[0]["message"]);
+        assert_eq!("node", lines[1]["nodeName"]);
+    }
+
+    #[test]
+    fn limit_running_exports() {
+        let exports = Arc::new(LogExports::default());
+        let first = exports.register((1, "a".to_string())).unwrap();
+        assert!(matches!(exports.register((1, "a".to_string())), Err(RPCError::InvalidParams(_))));
+        let _second = exports.register((1, "b".to_string())).unwrap();
+        assert!(matches!(exports.register((1, "c".to_string())), Err(RPCError::TooManyLogExports)));
+
+        drop(first);
+        let _third = exports.register((1, "c".to_string())).unwrap();
+
+        let others: Vec<_> = (2..2 + (MAX_EXPORTS / MAX_EXPORTS_PER_CONNECTION) as u32 - 1)
+            .flat_map(|connection_id| (0..MAX_EXPORTS_PER_CONNECTION).map(move |id| (connection_id, id.to_string())))
+            .map(|key| exports.register(key).unwrap())
+            .collect();
+        assert_eq!(MAX_EXPORTS - MAX_EXPORTS_PER_CONNECTION, others.len());
+        assert!(matches!(exports.register((100, "a".to_string())), Err(RPCError::TooManyLogExports)));
+    }
+
+    #[test]
+    fn acknowledge_running_export() {
+        let exports = Arc::new(LogExports::default());
+        let (registration, acknowledgements) = exports.register((1, "a".to_string())).unwrap();
+        assert!(exports.acknowledge(1, "a".to_string(), 0));
+        assert!(!exports.acknowledge(2, "a".to_string(), 0));
+
+        let mut acknowledged = None;
+        assert!(wait_acknowledgement(&acknowledgements, &mut acknowledged, 0).is_ok());
+        assert_eq!(Some(0), acknowledged);
+
+        drop(registration);
+        assert!(!exports.acknowledge(1, "a".to_string(), 1));
+    }
+}
@@ -1,9 +1,11 @@
 pub mod api;
 pub mod handler;
+mod log_export;
 pub mod service;
 pub mod types;
 
 pub use self::api::add_routing;
 pub use self::handler::WebSocketHandler;
+pub use self::log_export::LogExports;
 pub use self::service::{Message, Service, ServiceSender};
 pub use self::types::*;
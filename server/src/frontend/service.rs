@@ -1,10 +1,16 @@
+use super::super::common_rpc_types::{NodeName, StructuredLog};
+use super::super::db::LogFilter;
+use super::super::jsonrpc;
+use super::types::{EventFilter, EventType};
+use serde_json::json;
 use std::sync::mpsc::{channel, Sender};
 use std::thread;
 use std::vec::Vec;
 
 #[derive(Default)]
 pub struct Service {
-    web_sockets: Vec<ws::Sender>,
+    web_sockets: Vec<(ws::Sender, EventFilter)>,
+    log_subscriptions: Vec<(ws::Sender, LogFilter)>,
 }
 
 pub type ServiceSender = Sender<Message>;
@@ -12,7 +18,13 @@ pub type ServiceSender = Sender<Message>;
 pub enum Message {
     AddWS(ws::Sender),
     RemoveWS(ws::Sender),
-    SendEvent(String),
+    /// An event of the type about the nodes. The data is sent to the connections whose filter matches.
+    SendEvent(EventType, Vec<NodeName>, String),
+    SetEventFilter(ws::Sender, EventFilter),
+    SubscribeLogs(ws::Sender, LogFilter),
+    UnsubscribeLogs(ws::Sender),
+    /// The logs written by a node, with the tags of the node
+    SendLogs(NodeName, Vec<String>, Vec<StructuredLog>),
 }
 
 impl Service {
@@ -26,8 +38,11 @@ impl Service {
             .spawn(move || {
                 for message in rx {
                     match message {
-                        Message::SendEvent(jsonrpc_data) => {
-                            service.send_event(jsonrpc_data);
+                        Message::SendEvent(event_type, node_names, jsonrpc_data) => {
+                            service.send_event(event_type, &node_names, jsonrpc_data);
+                        }
+                        Message::SetEventFilter(web_socket, filter) => {
+                            service.set_event_filter(&web_socket, filter);
                         }
                         Message::AddWS(web_socket) => {
                             service.add_ws(web_socket);
@@ -35,6 +50,15 @@ impl Service {
                         Message::RemoveWS(web_socket) => {
                             service.remove_ws(web_socket);
                         }
+                        Message::SubscribeLogs(web_socket, filter) => {
+                            service.subscribe_logs(web_socket, filter);
+                        }
+                        Message::UnsubscribeLogs(web_socket) => {
+                            service.unsubscribe_logs(&web_socket);
+                        }
+                        Message::SendLogs(node_name, tags, logs) => {
+                            service.send_logs(&node_name, &tags, &logs);
+                        }
                     }
                 }
             })
@@ -43,8 +67,11 @@ impl Service {
         service_sender
     }
 
-    pub fn send_event(&mut self, data: String) {
-        for web_socket in &self.web_sockets {
+    pub fn send_event(&mut self, event_type: EventType, node_names: &[NodeName], data: String) {
+        for (web_socket, filter) in &self.web_sockets {
+            if !filter.matches(event_type, node_names) {
+                continue
+            }
             if let Err(err) = web_socket.send(data.clone()) {
                 cwarn!("Error when sending event to frontend {}", err);
             }
@@ -52,13 +79,14 @@ impl Service {
     }
 
     pub fn add_ws(&mut self, web_socket: ws::Sender) {
-        debug_assert_eq!(false, self.web_sockets.contains(&web_socket));
-        self.web_sockets.push(web_socket);
+        debug_assert_eq!(false, self.web_sockets.iter().any(|(web_socket_iter, _)| *web_socket_iter == web_socket));
+        self.web_sockets.push((web_socket, EventFilter::default()));
     }
 
     pub fn remove_ws(&mut self, web_socket: ws::Sender) {
-        debug_assert_eq!(true, self.web_sockets.contains(&web_socket));
-        let index = self.web_sockets.iter().position(|web_socket_iter| *web_socket_iter == web_socket);
+        debug_assert_eq!(true, self.web_sockets.iter().any(|(web_socket_iter, _)| *web_socket_iter == web_socket));
+        self.unsubscribe_logs(&web_socket);
+        let index = self.web_sockets.iter().position(|(web_socket_iter, _)| *web_socket_iter == web_socket);
         match index {
             None => cerror!("Cannot find websocket to delete, {:?}", web_socket.token()),
             Some(index) => {
@@ -66,4 +94,42 @@ impl Service {
             }
         }
     }
+
+    /// A new connection receives every event until it sets a filter.
+    pub fn set_event_filter(&mut self, web_socket: &ws::Sender, filter: EventFilter) {
+        match self.web_sockets.iter_mut().find(|(web_socket_iter, _)| web_socket_iter == web_socket) {
+            None => cerror!("Cannot find websocket to set the event filter, {:?}", web_socket.token()),
+            Some((_, current)) => *current = filter,
+        }
+    }
+
+    /// A connection has at most one log subscription. A new filter replaces the previous one.
+    pub fn subscribe_logs(&mut self, web_socket: ws::Sender, filter: LogFilter) {
+        self.unsubscribe_logs(&web_socket);
+        self.log_subscriptions.push((web_socket, filter));
+    }
+
+    pub fn unsubscribe_logs(&mut self, web_socket: &ws::Sender) {
+        self.log_subscriptions.retain(|(subscriber, _)| subscriber != web_socket);
+    }
+
+    pub fn send_logs(&mut self, node_name: &str, node_tags: &[String], logs: &[StructuredLog]) {
+        for (web_socket, filter) in &self.log_subscriptions {
+            let matched: Vec<&StructuredLog> =
+                logs.iter().filter(|log| filter.matches(node_name, node_tags, log)).collect();
+            if matched.is_empty() {
+                continue
+            }
+            let data = jsonrpc::serialize_notification(
+                "log_appended",
+                json!({
+                    "nodeName": node_name,
+                    "logs": matched,
+                }),
+            );
+            if let Err(err) = web_socket.send(data) {
+                cwarn!("Error when sending logs to frontend {}", err);
+            }
+        }
+    }
 }
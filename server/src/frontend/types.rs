@@ -1,21 +1,185 @@
 use super::super::common_rpc_types::{
-    self, BlackList, BlockId, GraphNetworkOutAllAVGRow, GraphNetworkOutAllRow, GraphNetworkOutNodeExtensionRow,
-    GraphNetworkOutNodePeerRow, HardwareInfo, HardwareUsage, NodeName, NodeStatus, NodeVersion, PendingTransaction,
-    WhiteList,
+    self, BlackList, BlockId, GraphBlockLagAllRow, GraphHardwareAllRow, GraphHardwareNodeRow, GraphNetworkOutAllAVGRow,
+    GraphNetworkOutAllRow, GraphNetworkOutNodeExtensionRow, GraphNetworkOutNodePeerRow, GraphPeerCountAllRow,
+    GraphPeerCountNodeRow, HardwareInfo, HardwareUsage, JobSchedule, MaterializedViewRefreshRow, NodeAction, NodeName,
+    NodeSelector, NodeStatus, NodeStatusHistoryRow, NodeVersion, PendingTransaction, TableStatsRow, WhiteList,
 };
-use super::super::{client, db};
-use serde_derive::Serialize;
+use super::super::node_control::NodeControl;
+use super::super::router::{Audit, Authorize};
+use super::super::rpc::RPCResponse;
+use super::super::{client, db, rollout};
+use super::log_export::LogExports;
+use crate::auth::{LoginLimiter, Role};
+use crate::config::{AuthConfig, NodeControlConfig, NodeRPCConfig, RetentionConfig};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Context {
     pub client_service: client::ServiceSender,
     pub db_service: db::ServiceSender,
-    pub passphrase: String,
+    pub auth_config: AuthConfig,
+    pub retention_config: RetentionConfig,
+    pub node_rpc_config: NodeRPCConfig,
+    pub node_control_config: NodeControlConfig,
+    /// The connection that sent the request
+    pub out: ws::Sender,
+    pub frontend_service: super::ServiceSender,
+    pub rollout_service: rollout::ServiceSender,
+    /// The session of the connection, shared by the contexts of its requests
+    pub session: Arc<RwLock<Option<db::Session>>>,
+    pub peer_addr: Option<SocketAddr>,
+    /// Shared by every connection
+    pub login_limiter: Arc<LoginLimiter>,
+    /// Shared by every connection
+    pub log_exports: Arc<LogExports>,
+}
+
+impl Context {
+    pub fn node_control(&self) -> NodeControl {
+        NodeControl::new(self.client_service.clone(), self.db_service.clone(), self.node_control_config.concurrency)
+    }
+
+    pub fn user_name(&self) -> Option<String> {
+        self.session.read().as_ref().map(|session| session.user_name.clone())
+    }
+}
+
+impl Authorize for Context {
+    /// The session is read from the DB on every call, so that a role change or a removal is applied at once.
+    /// The session of the connection is cleared if it is expired or signed out.
+    fn role(&self) -> Option<Role> {
+        let token = self.session.read().as_ref()?.token.clone();
+        match self.db_service.get_session(token) {
+            Ok(Some(session)) => {
+                let role = session.role;
+                *self.session.write() = Some(session);
+                Some(role)
+            }
+            Ok(None) => {
+                if self.session.write().take().is_some() {
+                    cinfo!("The session of {:?} is expired or signed out", self.peer_addr);
+                    if let Err(err) = self.frontend_service.send(super::Message::RemoveWS(self.out.clone())) {
+                        cerror!("Cannot remove the connection of the expired session: {}", err);
+                    }
+                }
+                None
+            }
+            Err(err) => {
+                cerror!("Cannot get the session: {}", err);
+                None
+            }
+        }
+    }
+}
+
+impl Audit for Context {
+    fn audit(&self, method: &str, params: &Value, result: &RPCResponse<Value>) {
+        let (result, error) = match result {
+            Ok(value) => (Some(value.clone().unwrap_or(Value::Null)), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+        let user_name = self.user_name();
+        cinfo!("{:?} called {} from {:?}", user_name, method, self.peer_addr);
+        self.db_service.write_audit_log(db::AuditLog {
+            time: Utc::now(),
+            user_name,
+            address: self.peer_addr.map(|peer_addr| peer_addr.to_string()),
+            method: method.to_string(),
+            params: params.clone(),
+            result,
+            error,
+        });
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditGetResponse {
+    pub rows: Vec<db::AuditLog>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobCreateRequest {
+    pub name: String,
+    pub nodes: NodeSelector,
+    pub action: NodeAction,
+    pub schedule: JobSchedule,
+}
+
+/// The arguments are optional, so that the callers without the tags can still send `[]`.
+/// Empty tags select every node.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DashboardGetNetworkRequest {
+    pub tags: Vec<String>,
+}
+
+/// Selects the runs of a job, or of every job if `job_id` is None.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobGetHistoryRequest {
+    #[serde(default)]
+    pub job_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodesControlResponse {
+    pub results: Vec<NodeControlResult>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthLoginResponse {
+    pub token: String,
+    pub role: Role,
+    pub expires_at: DateTime<Utc>,
 }
 
 pub type Event = String;
 
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum EventType {
+    #[serde(rename = "dashboard_updated")]
+    DashboardUpdated,
+    #[serde(rename = "node_updated")]
+    NodeUpdated,
+    #[serde(rename = "rollout_updated")]
+    RolloutUpdated,
+}
+
+/// Selects the notifications sent to a connection. Empty conditions match every event.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EventFilter {
+    #[serde(default)]
+    pub event_types: Vec<EventType>,
+    #[serde(default)]
+    pub node_names: Vec<NodeName>,
+}
+
+impl EventFilter {
+    /// `node_names` is empty for the network-wide events such as a chain fork, which are sent regardless of the node names.
+    pub fn matches(&self, event_type: EventType, node_names: &[NodeName]) -> bool {
+        if !self.event_types.is_empty() && !self.event_types.contains(&event_type) {
+            return false
+        }
+        if !self.node_names.is_empty()
+            && !node_names.is_empty()
+            && !node_names.iter().any(|name| self.node_names.contains(name))
+        {
+            return false
+        }
+        true
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
@@ -95,6 +259,7 @@ pub struct NodeGetInfoResponse {
     pub blacklist: Option<BlackList>,
     pub hardware: Option<HardwareInfo>,
     pub events: Vec<Event>,
+    pub tags: Vec<String>,
 }
 
 impl NodeGetInfoResponse {
@@ -138,6 +303,7 @@ impl NodeGetInfoResponse {
                 },
             }),
             events: vec!["Network connected".to_string(), "Block received".to_string()],
+            tags: Vec::new(),
         }
     }
 
@@ -169,6 +335,12 @@ pub struct LogGetTargetsResponse {
 
 pub type LogGetRequest = db::LogQueryParams;
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogGetHistogramResponse {
+    pub rows: Vec<db::LogHistogramRow>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LogGetResponse {
@@ -198,3 +370,137 @@ pub struct GraphNetworkOutNodeExtensionResponse {
 pub struct GraphNetworkOutNodePeerResponse {
     pub rows: Vec<GraphNetworkOutNodePeerRow>,
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphHardwareNodeResponse {
+    pub rows: Vec<GraphHardwareNodeRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphHardwareAllResponse {
+    pub rows: Vec<GraphHardwareAllRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphBlockLagAllResponse {
+    pub rows: Vec<GraphBlockLagAllRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPeerCountNodeResponse {
+    pub rows: Vec<GraphPeerCountNodeRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphPeerCountAllResponse {
+    pub rows: Vec<GraphPeerCountAllRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionGetStatusResponse {
+    pub config: RetentionConfig,
+    pub tables: Vec<TableStatsRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterializedViewGetStatusResponse {
+    pub views: Vec<MaterializedViewRefreshRow>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeGetStatusHistoryResponse {
+    pub history: Vec<NodeStatusHistoryRow>,
+    /// The percentage of the time in the `Run` status among the time whose status is known.
+    pub uptime: Option<f64>,
+}
+
+impl NodeGetStatusHistoryResponse {
+    /// The disconnections of the agents are recorded as `Error`, so the last status lasts until `to` or now.
+    pub fn from_history(history: Vec<NodeStatusHistoryRow>, to: DateTime<Utc>) -> Self {
+        let end = std::cmp::min(to, Utc::now());
+        let mut total = 0i64;
+        let mut run = 0i64;
+        for (index, row) in history.iter().enumerate() {
+            let until = history.get(index + 1).map(|next| next.time).unwrap_or(end);
+            let duration = (until - row.time).num_milliseconds().max(0);
+            total += duration;
+            if row.status == NodeStatus::Run {
+                run += duration;
+            }
+        }
+        let uptime = if total > 0 {
+            Some(run as f64 * 100.0 / total as f64)
+        } else {
+            None
+        };
+        Self {
+            history,
+            uptime,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(status: NodeStatus, time: &str) -> NodeStatusHistoryRow {
+        NodeStatusHistoryRow {
+            status,
+            time: DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&Utc),
+        }
+    }
+
+    #[test]
+    fn uptime_of_status_history() {
+        let history = vec![
+            row(NodeStatus::Run, "2019-01-01T00:00:00Z"),
+            row(NodeStatus::Error, "2019-01-01T06:00:00Z"),
+            row(NodeStatus::Run, "2019-01-01T12:00:00Z"),
+        ];
+        let to = DateTime::parse_from_rfc3339("2019-01-02T00:00:00Z").unwrap().with_timezone(&Utc);
+        let response = NodeGetStatusHistoryResponse::from_history(history, to);
+        assert_eq!(Some(75), response.uptime.map(|uptime| uptime.round() as i64));
+    }
+
+    #[test]
+    fn unknown_uptime_without_history() {
+        let response = NodeGetStatusHistoryResponse::from_history(Vec::new(), Utc::now());
+        assert_eq!(None, response.uptime);
+    }
+
+    #[test]
+    fn dashboard_get_network_request_without_tags() {
+        let req: DashboardGetNetworkRequest = serde_json::from_value(serde_json::json!([])).unwrap();
+        assert!(req.tags.is_empty());
+        let req: DashboardGetNetworkRequest = serde_json::from_value(serde_json::json!([["validator"]])).unwrap();
+        assert_eq!(vec!["validator".to_string()], req.tags);
+    }
+
+    #[test]
+    fn empty_event_filter_matches_every_event() {
+        let filter = EventFilter::default();
+        assert!(filter.matches(EventType::DashboardUpdated, &[]));
+        assert!(filter.matches(EventType::NodeUpdated, &["a".to_string()]));
+    }
+
+    #[test]
+    fn event_filter_by_type_and_node() {
+        let filter = EventFilter {
+            event_types: vec![EventType::NodeUpdated],
+            node_names: vec!["a".to_string()],
+        };
+        assert!(filter.matches(EventType::NodeUpdated, &["a".to_string()]));
+        assert!(!filter.matches(EventType::NodeUpdated, &["b".to_string()]));
+        assert!(!filter.matches(EventType::DashboardUpdated, &["a".to_string()]));
+        assert!(filter.matches(EventType::NodeUpdated, &[]));
+    }
+}
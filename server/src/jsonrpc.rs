@@ -75,7 +75,11 @@ where
                     .into(),
                 ),
                 Err(RouterError::RPC(err)) => {
-                    cwarn!("Error while handling {}({:#?}) : {}", method, params, err);
+                    if is_auth_method(&method) {
+                        cwarn!("Error while handling {} : {}", method, err);
+                    } else {
+                        cwarn!("Error while handling {}({:#?}) : {}", method, params, err);
+                    }
                     Some(
                         Failure {
                             jsonrpc: None,
@@ -92,6 +96,11 @@ where
     response.map(|response| serde_json::to_string(&response).expect("Should success serialize"))
 }
 
+/// The requests and the responses of the auth methods have passwords or session tokens, which should not be logged.
+pub fn is_auth_method(method: &str) -> bool {
+    method.starts_with("auth_")
+}
+
 pub fn invalid_format() -> String {
     serde_json::to_string(&Failure {
         jsonrpc: None,
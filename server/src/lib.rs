@@ -1,4 +1,7 @@
 #[macro_use]
 pub mod logger;
+pub mod auth;
+pub mod config;
+pub mod migration;
 
 pub use logger::init as logger_init;
@@ -6,93 +6,161 @@ mod cron;
 mod daily_reporter;
 mod db;
 mod event_propagator;
+mod fork_detector;
 mod frontend;
 mod jsonrpc;
+mod node_control;
 mod noti;
+mod rollout;
 mod router;
 mod rpc;
+mod scheduler;
+mod tls;
 mod util;
 
 use self::event_propagator::EventPropagator;
 use self::logger::init as logger_init;
 use self::noti::NotiBuilder;
 use self::router::Router;
+use codechain_dashboard_server::auth;
+use codechain_dashboard_server::config::{self, Config};
+use codechain_dashboard_server::migration;
+use openssl::ssl::SslAcceptor;
+use postgres::{Connection, TlsMode};
 use std::sync::Arc;
 use std::thread;
-use ws::listen;
 
 fn main() {
     logger_init().expect("Logger should be initialized");
 
+    let config = Config::load().unwrap_or_else(|err| panic!("{}", err));
+    let network_id = config.network_id.clone().expect("network_id should be set in the config file or NETWORK_ID");
+    check_schema(&config.db);
+
     let mut noti_builder = NotiBuilder::default();
-    if let Ok(slack_hook_url) = std::env::var("SLACK_WEBHOOK_URL") {
+    if let Some(slack) = &config.noti.slack {
         cinfo!("Set slack");
-        noti_builder.slack(slack_hook_url);
+        noti_builder.slack(slack.webhook_url.clone());
     }
-    match (std::env::var("SENDGRID_API_KEY"), std::env::var("SENDGRID_TO")) {
-        (Ok(api_key), Ok(to)) => {
-            cinfo!("Set email to {}", to);
-            noti_builder.sendgrid(api_key, to);
-        }
-        (Ok(_), _) => {
-            panic!("You set a sendgrid api key, but not a destination");
-        }
-        (_, Ok(_)) => {
-            panic!("You set a sendgrid destination, but not an api key");
-        }
-        _ => {}
+    if let Some(sendgrid) = &config.noti.sendgrid {
+        cinfo!("Set email to {}", sendgrid.to);
+        noti_builder.sendgrid(sendgrid.api_key.clone(), sendgrid.to.clone());
     }
     let noti = noti_builder.build();
 
-    // FIXME: move to config
-    let db_user = "codechain-dashboard-server";
-    let db_password = "preempt-entreat-bell-chanson";
-
     let frontend_service_sender = frontend::Service::run_thread();
     let event_propagator = Box::new(EventPropagator::new(frontend_service_sender.clone()));
+    let frontend_service_for_fork_detector = frontend_service_sender.clone();
     let db_service_sender = db::Service::run_thread(db::ServiceNewArg {
         event_subscriber: event_propagator,
-        db_user: db_user.to_string(),
-        db_password: db_password.to_string(),
+        db_config: config.db.clone(),
     });
-    let client_service_sender = client::Service::run_thread(db_service_sender.clone(), Arc::clone(&noti));
+    let client_service_sender = client::Service::run_thread(
+        db_service_sender.clone(),
+        Arc::clone(&noti),
+        config.client.clone(),
+        config.alert.clone(),
+    );
     let client_service_for_frontend = client_service_sender.clone();
+    let rollout_service_sender = rollout::Service::run_thread(
+        db_service_sender.clone(),
+        client_service_sender.clone(),
+        frontend_service_sender.clone(),
+        config.node_control.concurrency,
+    );
 
     let db_service_sender_for_frontend = db_service_sender.clone();
+    let frontend_listen = config.listen.frontend;
+    let frontend_ssl = config.listen.frontend_tls.as_ref().map(load_tls_acceptor);
+    let auth_config = config.auth.clone();
+    let retention_config = config.retention.clone();
+    let node_rpc_config = config.node_rpc.clone();
+    let node_control_config = config.node_control.clone();
+    let login_limiter = Arc::new(auth::LoginLimiter::default());
+    let log_exports = Arc::new(frontend::LogExports::default());
     let frontend_join = thread::Builder::new()
         .name("frontend listen".to_string())
         .spawn(move || {
             let mut frontend_router = Arc::new(Router::new());
             frontend::add_routing(Arc::get_mut(&mut frontend_router).unwrap());
-            let frontend_context = frontend::Context {
-                client_service: client_service_for_frontend,
-                db_service: db_service_sender_for_frontend.clone(),
-                passphrase: std::env::var("PASSPHRASE").unwrap_or_else(|_| "passphrase".to_string()),
-            };
-            listen("0.0.0.0:3012", move |out| frontend::WebSocketHandler {
+            let encrypt = frontend_ssl.is_some();
+            tls::listen(frontend_listen, encrypt, move |out| frontend::WebSocketHandler {
+                context: frontend::Context {
+                    client_service: client_service_for_frontend.clone(),
+                    db_service: db_service_sender_for_frontend.clone(),
+                    auth_config: auth_config.clone(),
+                    retention_config: retention_config.clone(),
+                    node_rpc_config: node_rpc_config.clone(),
+                    node_control_config: node_control_config.clone(),
+                    out: out.clone(),
+                    frontend_service: frontend_service_sender.clone(),
+                    rollout_service: rollout_service_sender.clone(),
+                    session: Default::default(),
+                    peer_addr: None,
+                    login_limiter: Arc::clone(&login_limiter),
+                    log_exports: Arc::clone(&log_exports),
+                },
                 out,
-                context: frontend_context.clone(),
                 router: Arc::clone(&frontend_router),
                 frontend_service: frontend_service_sender.clone(),
+                ssl: frontend_ssl.clone(),
             })
             .unwrap();
         })
         .expect("Should success listening frontend");
 
     let client_service_for_client = client_service_sender.clone();
+    let client_listen = config.listen.client;
+    let client_ssl = config.listen.client_tls.as_ref().map(load_tls_acceptor);
+    let client_config = Arc::new(config.client.clone());
+    if !client_config.requires_auth() {
+        cwarn!("Every agent is accepted because no client key is set");
+    }
     let client_join = thread::Builder::new()
         .name("client listen".to_string())
         .spawn(move || {
-            listen("0.0.0.0:4012", |out| client::WebSocketHandler::new(out, client_service_for_client.clone()))
-                .unwrap();
+            tls::listen(client_listen, client_ssl.is_some(), |out| {
+                client::WebSocketHandler::new(
+                    out,
+                    client_service_for_client.clone(),
+                    Arc::clone(&client_config),
+                    client_ssl.clone(),
+                )
+            })
+            .unwrap();
         })
         .expect("Should success listening client");
 
-    cron::remove_network_usage::run(db_user, db_password);
+    cron::retention::run(&config.db, &config.retention);
+    cron::refresh_materialized_view::run(&config.db, &config.cron);
 
-    let daily_reporter_join = daily_reporter::start(noti, db_service_sender, client_service_sender);
+    let fork_detector_join = fork_detector::start(
+        Arc::clone(&noti),
+        db_service_sender.clone(),
+        client_service_sender.clone(),
+        frontend_service_for_fork_detector,
+        network_id.clone(),
+    );
+    let scheduler_join =
+        scheduler::start(db_service_sender.clone(), client_service_sender.clone(), config.node_control.concurrency);
+    let daily_reporter_join = daily_reporter::start(noti, db_service_sender, client_service_sender, network_id);
 
     frontend_join.join().expect("Join frontend listener");
     client_join.join().expect("Join client listener");
+    fork_detector_join.join().expect("Join fork detector");
+    scheduler_join.join().expect("Join scheduler");
     daily_reporter_join.join().expect("Join daily reporter");
 }
+
+fn load_tls_acceptor(tls_config: &config::TlsConfig) -> Arc<SslAcceptor> {
+    let acceptor = tls::acceptor(tls_config)
+        .unwrap_or_else(|err| panic!("Cannot load the certificate {}: {}", tls_config.certificate, err));
+    Arc::new(acceptor)
+}
+
+fn check_schema(db_config: &config::DBConfig) {
+    let conn = Connection::connect(db_config.url(), TlsMode::None).expect("Connect to the DB");
+    if let Err(err) = migration::check(&conn) {
+        panic!("{}", err);
+    }
+}
@@ -0,0 +1,290 @@
+use postgres::Connection;
+use std::fmt;
+
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    /// False for the migrations that can't run in a transaction, such as `CREATE INDEX CONCURRENTLY`.
+    /// Their statements run one by one, so they should be safe to run again after a failure.
+    pub transaction: bool,
+}
+
+/// Generated columns require PostgreSQL 12 or later.
+const MIN_SERVER_VERSION: i32 = 120000;
+
+/// Every schema change is appended here with the next version. Never modify a migration that is already released.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        up: include_str!("../migrations/0001_initial.up.sql"),
+        down: include_str!("../migrations/0001_initial.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 2,
+        name: "node_status_history",
+        up: include_str!("../migrations/0002_node_status_history.up.sql"),
+        down: include_str!("../migrations/0002_node_status_history.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 3,
+        name: "hardware_usage",
+        up: include_str!("../migrations/0003_hardware_usage.up.sql"),
+        down: include_str!("../migrations/0003_hardware_usage.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 4,
+        name: "best_block",
+        up: include_str!("../migrations/0004_best_block.up.sql"),
+        down: include_str!("../migrations/0004_best_block.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 5,
+        name: "materialized_view_unique_index",
+        up: include_str!("../migrations/0005_materialized_view_unique_index.up.sql"),
+        down: include_str!("../migrations/0005_materialized_view_unique_index.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 6,
+        name: "logs_full_text_search",
+        up: include_str!("../migrations/0006_logs_full_text_search.up.sql"),
+        down: include_str!("../migrations/0006_logs_full_text_search.down.sql"),
+        transaction: false,
+    },
+    Migration {
+        version: 7,
+        name: "users",
+        up: include_str!("../migrations/0007_users.up.sql"),
+        down: include_str!("../migrations/0007_users.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 8,
+        name: "audit_log",
+        up: include_str!("../migrations/0008_audit_log.up.sql"),
+        down: include_str!("../migrations/0008_audit_log.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 9,
+        name: "rollouts",
+        up: include_str!("../migrations/0009_rollouts.up.sql"),
+        down: include_str!("../migrations/0009_rollouts.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 10,
+        name: "node_tags",
+        up: include_str!("../migrations/0010_node_tags.up.sql"),
+        down: include_str!("../migrations/0010_node_tags.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 11,
+        name: "jobs",
+        up: include_str!("../migrations/0011_jobs.up.sql"),
+        down: include_str!("../migrations/0011_jobs.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 12,
+        name: "retention_time_indexes",
+        up: include_str!("../migrations/0012_retention_time_indexes.up.sql"),
+        down: include_str!("../migrations/0012_retention_time_indexes.down.sql"),
+        transaction: true,
+    },
+    Migration {
+        version: 13,
+        name: "materialized_view_refreshes",
+        up: include_str!("../migrations/0013_materialized_view_refreshes.up.sql"),
+        down: include_str!("../migrations/0013_materialized_view_refreshes.down.sql"),
+        transaction: true,
+    },
+];
+
+#[derive(Debug)]
+pub enum Error {
+    DB(postgres::Error),
+    Outdated {
+        current: i32,
+        latest: i32,
+    },
+    UnknownVersion {
+        current: i32,
+        latest: i32,
+    },
+    UnsupportedServer {
+        version: i32,
+    },
+}
+
+impl From<postgres::Error> for Error {
+    fn from(err: postgres::Error) -> Self {
+        Error::DB(err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DB(err) => write!(f, "Migration failed: {}", err),
+            Error::Outdated {
+                current,
+                latest,
+            } => write!(f, "The DB schema version is {} but {} is required. Run `migrate up` first", current, latest),
+            Error::UnknownVersion {
+                current,
+                latest,
+            } => write!(f, "The DB schema version {} is newer than the latest known version {}", current, latest),
+            Error::UnsupportedServer {
+                version,
+            } => write!(
+                f,
+                "PostgreSQL {}.{} is not supported. PostgreSQL {} or later is required",
+                version / 10000,
+                version % 10000,
+                MIN_SERVER_VERSION / 10000
+            ),
+        }
+    }
+}
+
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map(|migration| migration.version).unwrap_or(0)
+}
+
+fn create_migrations_table(conn: &Connection) -> postgres::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name VARCHAR NOT NULL,
+            applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+        )",
+        &[],
+    )?;
+    Ok(())
+}
+
+pub fn current_version(conn: &Connection) -> postgres::Result<i32> {
+    create_migrations_table(conn)?;
+    let rows = conn.query("SELECT COALESCE(MAX(version), 0) FROM schema_migrations", &[])?;
+    Ok(rows.get(0).get(0))
+}
+
+fn server_version(conn: &Connection) -> postgres::Result<i32> {
+    let rows = conn.query("SELECT current_setting('server_version_num')::INTEGER", &[])?;
+    Ok(rows.get(0).get(0))
+}
+
+fn check_server_version(conn: &Connection) -> Result<(), Error> {
+    let version = server_version(conn)?;
+    if version < MIN_SERVER_VERSION {
+        return Err(Error::UnsupportedServer {
+            version,
+        })
+    }
+    Ok(())
+}
+
+/// Fails if the schema is not at the latest version, so the server never runs against a schema it doesn't know.
+/// Fails on a PostgreSQL server older than the migrations support.
+pub fn check(conn: &Connection) -> Result<(), Error> {
+    check_server_version(conn)?;
+    let current = current_version(conn)?;
+    let latest = latest_version();
+    if current < latest {
+        return Err(Error::Outdated {
+            current,
+            latest,
+        })
+    }
+    if current > latest {
+        return Err(Error::UnknownVersion {
+            current,
+            latest,
+        })
+    }
+    Ok(())
+}
+
+/// Applies the migrations newer than the current version up to `target`, each in its own transaction unless it can't
+/// run in a transaction.
+pub fn up(conn: &Connection, target: i32) -> Result<(), Error> {
+    check_server_version(conn)?;
+    let current = current_version(conn)?;
+    for migration in MIGRATIONS.iter().filter(|migration| current < migration.version && migration.version <= target) {
+        cinfo!("Apply migration {} {}", migration.version, migration.name);
+        if !migration.transaction {
+            for statement in statements(migration.up) {
+                conn.batch_execute(statement)?;
+            }
+            conn.execute("INSERT INTO schema_migrations (version, name) VALUES ($1, $2)", &[
+                &migration.version,
+                &migration.name,
+            ])?;
+            continue
+        }
+        let transaction = conn.transaction()?;
+        transaction.batch_execute(migration.up)?;
+        transaction.execute("INSERT INTO schema_migrations (version, name) VALUES ($1, $2)", &[
+            &migration.version,
+            &migration.name,
+        ])?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+/// Splits the SQL at `;`, so the comments of a migration without a transaction must not contain it.
+/// The statements are sent one by one, because the statements sent in a query run in a transaction.
+fn statements(sql: &str) -> impl Iterator<Item = &str> {
+    sql.split(';').map(str::trim).filter(|statement| !statement.is_empty())
+}
+
+/// Reverts the applied migrations newer than `target`, from the newest one.
+pub fn down(conn: &Connection, target: i32) -> Result<(), Error> {
+    let current = current_version(conn)?;
+    if current > latest_version() {
+        return Err(Error::UnknownVersion {
+            current,
+            latest: latest_version(),
+        })
+    }
+    for migration in
+        MIGRATIONS.iter().rev().filter(|migration| target < migration.version && migration.version <= current)
+    {
+        cinfo!("Revert migration {} {}", migration.version, migration.name);
+        let transaction = conn.transaction()?;
+        transaction.batch_execute(migration.down)?;
+        transaction.execute("DELETE FROM schema_migrations WHERE version=$1", &[&migration.version])?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_sequential() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(index as i32 + 1, migration.version, "{}", migration.name);
+        }
+    }
+
+    #[test]
+    fn split_statements() {
+        let statements: Vec<_> = statements(MIGRATIONS[5].up).collect();
+        assert_eq!(3, statements.len());
+        assert!(statements[0].ends_with("STORED"));
+        assert!(statements[2].starts_with("CREATE INDEX CONCURRENTLY"));
+    }
+}
@@ -0,0 +1,218 @@
+use super::client::{SendClientRPC, ServiceSender as ClientServiceSender};
+use super::common_rpc_types::{
+    NodeAction, NodeControlResult, NodeName, NodeSelector, ShellStartCodeChainRequest, UpdateCodeChainRequest,
+};
+use super::db::ServiceSender as DBServiceSender;
+use super::rpc::{RPCError, RPCResult};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+/// The error code of the agent when CodeChain is not running.
+const ERR_CODECHAIN_NOT_RUNNING: i64 = 0;
+
+/// Starts, stops and updates the nodes through their agents.
+/// The nodes are started with the environment and the arguments that they were started with last time.
+#[derive(Clone)]
+pub struct NodeControl {
+    client_service: ClientServiceSender,
+    db_service: DBServiceSender,
+    /// The number of nodes controlled at once by `run`
+    concurrency: usize,
+}
+
+impl NodeControl {
+    pub fn new(client_service: ClientServiceSender, db_service: DBServiceSender, concurrency: usize) -> Self {
+        Self {
+            client_service,
+            db_service,
+            concurrency,
+        }
+    }
+
+    /// Returns the selected names in the order of the request without duplicates.
+    pub fn select(&self, selector: NodeSelector) -> RPCResult<Vec<NodeName>> {
+        let node_tags = if selector.tags.is_empty() {
+            HashMap::new()
+        } else {
+            self.db_service.get_node_tags()?
+        };
+        let all_names = if selector.all {
+            self.db_service.get_clients_state()?.into_iter().map(|client| client.name).collect()
+        } else {
+            Vec::new()
+        };
+        select_names(selector, &node_tags, all_names)
+    }
+
+    /// Calls `control` for every node, `concurrency` nodes in parallel at a time.
+    /// A failure of a node doesn't stop the others.
+    pub fn run<F>(&self, names: Vec<NodeName>, control: F) -> Vec<NodeControlResult>
+    where
+        F: Fn(&NodeControl, &str) -> RPCResult<()> + Send + Sync + 'static, {
+        let control = Arc::new(control);
+        let mut results = Vec::with_capacity(names.len());
+        for chunk in names.chunks(self.concurrency.max(1)) {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|name| {
+                    let node_control = self.clone();
+                    let control = Arc::clone(&control);
+                    let thread_name = name.clone();
+                    (name.clone(), thread::spawn(move || control(&node_control, &thread_name)))
+                })
+                .collect();
+            results.extend(handles.into_iter().map(|(name, handle)| {
+                let error = match handle.join() {
+                    Ok(Ok(())) => None,
+                    Ok(Err(err)) => Some(err.to_string()),
+                    Err(_) => Some("The request is panicked".to_string()),
+                };
+                NodeControlResult {
+                    name,
+                    error,
+                }
+            }));
+        }
+        results
+    }
+
+    pub fn apply(&self, name: &str, action: &NodeAction) -> RPCResult<()> {
+        match action {
+            NodeAction::Start => self.start(name),
+            NodeAction::Stop => self.stop(name),
+            NodeAction::Restart => self.restart(name),
+            NodeAction::Update {
+                update,
+            } => self.update(name, update.clone()),
+        }
+    }
+
+    pub fn start(&self, name: &str) -> RPCResult<()> {
+        let client = self.client_service.get_client(name).ok_or(RPCError::ClientNotFound)?;
+        client.shell_start_codechain(self.start_option(name)?)
+    }
+
+    pub fn stop(&self, name: &str) -> RPCResult<()> {
+        let client = self.client_service.get_client(name).ok_or(RPCError::ClientNotFound)?;
+        client.shell_stop_codechain()
+    }
+
+    /// Starts a stopped node too.
+    pub fn restart(&self, name: &str) -> RPCResult<()> {
+        ignore_not_running(self.stop(name))?;
+        self.start(name)
+    }
+
+    pub fn update(&self, name: &str, req: UpdateCodeChainRequest) -> RPCResult<()> {
+        let client = self.client_service.get_client(name).ok_or(RPCError::ClientNotFound)?;
+        client.shell_update_codechain((self.start_option(name)?, req))
+    }
+
+    fn start_option(&self, name: &str) -> RPCResult<ShellStartCodeChainRequest> {
+        let extra = self.db_service.get_client_extra(name.to_string())?;
+        let (env, args) = extra.map(|extra| (extra.prev_env, extra.prev_args)).unwrap_or_default();
+        Ok(ShellStartCodeChainRequest {
+            env,
+            args,
+        })
+    }
+}
+
+/// `node_tags` and `all_names` are used only when the selector has tags and `all` respectively.
+fn select_names(
+    selector: NodeSelector,
+    node_tags: &HashMap<NodeName, Vec<String>>,
+    all_names: Vec<NodeName>,
+) -> RPCResult<Vec<NodeName>> {
+    let mut selected = selector.node_names;
+    if !selector.tags.is_empty() {
+        let mut tagged: Vec<NodeName> =
+            node_tags.keys().filter(|name| has_any_tag(node_tags, name, &selector.tags)).cloned().collect();
+        tagged.sort();
+        selected.extend(tagged);
+    }
+    if selector.all {
+        selected.extend(all_names);
+    }
+
+    let mut names: Vec<NodeName> = Vec::with_capacity(selected.len());
+    for name in selected {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        return Err(RPCError::InvalidParams("No node is selected".to_string()))
+    }
+    Ok(names)
+}
+
+fn ignore_not_running(result: RPCResult<()>) -> RPCResult<()> {
+    match result {
+        Err(RPCError::FromClient(err)) if err.code.code() == ERR_CODECHAIN_NOT_RUNNING => Ok(()),
+        result => result,
+    }
+}
+
+pub fn has_any_tag(node_tags: &HashMap<NodeName, Vec<String>>, name: &str, tags: &[String]) -> bool {
+    node_tags.get(name).map_or(false, |node_tags| node_tags.iter().any(|tag| tags.contains(tag)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::types::{Error as JSONRPCError, ErrorCode};
+
+    fn names(names: &[&str]) -> Vec<NodeName> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn select_without_duplicates_in_the_requested_order() {
+        let mut node_tags = HashMap::new();
+        node_tags.insert("c".to_string(), names(&["seed"]));
+        node_tags.insert("a".to_string(), names(&["seed", "validator"]));
+        node_tags.insert("d".to_string(), names(&["validator"]));
+        let selector = NodeSelector {
+            node_names: names(&["b", "a", "b"]),
+            tags: names(&["seed"]),
+            all: false,
+        };
+        assert_eq!(names(&["b", "a", "c"]), select_names(selector, &node_tags, Vec::new()).unwrap());
+    }
+
+    #[test]
+    fn select_all() {
+        let selector = NodeSelector {
+            node_names: names(&["b"]),
+            tags: Vec::new(),
+            all: true,
+        };
+        assert_eq!(names(&["b", "a", "c"]), select_names(selector, &HashMap::new(), names(&["a", "b", "c"])).unwrap());
+    }
+
+    #[test]
+    fn select_nothing() {
+        let result = select_names(NodeSelector::default(), &HashMap::new(), Vec::new());
+        assert!(matches!(result, Err(RPCError::InvalidParams(_))));
+
+        let selector = NodeSelector {
+            node_names: Vec::new(),
+            tags: names(&["seed"]),
+            all: false,
+        };
+        let result = select_names(selector, &HashMap::new(), Vec::new());
+        assert!(matches!(result, Err(RPCError::InvalidParams(_))));
+    }
+
+    #[test]
+    fn restart_ignores_only_that_codechain_is_not_running() {
+        let not_running = JSONRPCError::new(ErrorCode::ServerError(ERR_CODECHAIN_NOT_RUNNING));
+        assert!(ignore_not_running(Err(RPCError::FromClient(not_running))).is_ok());
+
+        let updating = JSONRPCError::new(ErrorCode::ServerError(-10003));
+        assert!(ignore_not_running(Err(RPCError::FromClient(updating))).is_err());
+        assert!(ignore_not_running(Err(RPCError::ClientNotFound)).is_err());
+    }
+}
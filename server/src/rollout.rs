@@ -0,0 +1,435 @@
+use super::client::client::UPDATE_INTERVAL;
+use super::client::ServiceSender as ClientServiceSender;
+use super::common_rpc_types::{
+    NodeName, NodeStatus, Rollout, RolloutErrorPolicy, RolloutNode, RolloutNodeStatus, RolloutStatus,
+    UpdateCodeChainRequest, UpdateRolloutRequest,
+};
+use super::db::{ClientQueryResult, ServiceSender as DBServiceSender};
+use super::node_control::NodeControl;
+use super::rpc::{RPCError, RPCResult};
+use super::{frontend, jsonrpc};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A node that does not run the new version and catch up in this time is regarded as failed.
+const NODE_UPDATE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// A node is caught up when its best block is behind the best block of the network by at most this.
+const CATCH_UP_MARGIN: i64 = 2;
+
+pub enum Message {
+    /// Creates a rollout and returns its id
+    Start(UpdateRolloutRequest, Option<String>, Sender<Result<i64, String>>),
+    Resume(i64, Sender<Result<(), String>>),
+    Abort(i64, Sender<Result<(), String>>),
+    /// Sent by the worker of the rollout when it stops
+    Finished(i64),
+}
+
+#[derive(Clone)]
+pub struct ServiceSender {
+    sender: Sender<Message>,
+}
+
+enum Control {
+    Resume(Sender<Result<(), String>>),
+    Abort(Sender<Result<(), String>>),
+}
+
+pub struct Service {
+    sender: Sender<Message>,
+    db_service: DBServiceSender,
+    node_control: NodeControl,
+    frontend_service: frontend::ServiceSender,
+    /// The nodes and the control channels of the running and paused rollouts
+    workers: HashMap<i64, (Vec<NodeName>, Sender<Control>)>,
+}
+
+impl Service {
+    pub fn run_thread(
+        db_service: DBServiceSender,
+        client_service: ClientServiceSender,
+        frontend_service: frontend::ServiceSender,
+        node_control_concurrency: usize,
+    ) -> ServiceSender {
+        let (sender, rx) = channel();
+        let service_sender = ServiceSender {
+            sender: sender.clone(),
+        };
+
+        let mut service = Service {
+            sender,
+            node_control: NodeControl::new(client_service, db_service.clone(), node_control_concurrency),
+            db_service,
+            frontend_service,
+            workers: HashMap::new(),
+        };
+
+        thread::Builder::new()
+            .name("rollout service".to_string())
+            .spawn(move || {
+                for message in rx {
+                    match message {
+                        Message::Start(request, user_name, callback) => {
+                            let result = service.start(request, user_name);
+                            if let Err(callback_err) = callback.send(result) {
+                                cerror!("Error at {}", callback_err);
+                            }
+                        }
+                        Message::Resume(id, callback) => {
+                            service.control(id, Control::Resume(callback));
+                        }
+                        Message::Abort(id, callback) => {
+                            service.control(id, Control::Abort(callback));
+                        }
+                        Message::Finished(id) => {
+                            service.workers.remove(&id);
+                        }
+                    }
+                }
+            })
+            .expect("Should success running rollout service thread");
+
+        service_sender
+    }
+
+    fn start(&mut self, request: UpdateRolloutRequest, user_name: Option<String>) -> Result<i64, String> {
+        if request.node_names.is_empty() {
+            return Err("No node to update".to_string())
+        }
+        if request.batch_size == 0 {
+            return Err("The batch size should be positive".to_string())
+        }
+        if let Some(name) = self.updating_node(&request.node_names) {
+            return Err(format!("{} is in another rollout", name))
+        }
+
+        let now = Utc::now();
+        let mut rollout = Rollout {
+            id: 0,
+            user_name,
+            status: RolloutStatus::Running,
+            nodes: plan_waves(&request.node_names, request.batch_size),
+            request,
+            started_at: now,
+            updated_at: now,
+        };
+        rollout.id = self.db_service.create_rollout(rollout.clone()).map_err(|err| format!("{:?}", err))?;
+        cinfo!("Rollout {} is started", rollout.id);
+
+        let (control_sender, control_receiver) = channel();
+        self.workers.insert(rollout.id, (rollout.request.node_names.clone(), control_sender));
+        let mut worker = Worker {
+            rollout,
+            control: control_receiver,
+            db_service: self.db_service.clone(),
+            node_control: self.node_control.clone(),
+            frontend_service: self.frontend_service.clone(),
+        };
+        let id = worker.rollout.id;
+        let service = self.sender.clone();
+        thread::Builder::new()
+            .name(format!("rollout {}", id))
+            .spawn(move || {
+                worker.run();
+                service.send(Message::Finished(id)).expect("Should success send request");
+            })
+            .expect("Should success running rollout thread");
+        Ok(id)
+    }
+
+    /// Returns a node of the request that is in an unfinished rollout.
+    fn updating_node(&self, node_names: &[NodeName]) -> Option<NodeName> {
+        self.workers.values().flat_map(|(names, _)| names).find(|name| node_names.contains(name)).cloned()
+    }
+
+    fn control(&self, id: i64, control: Control) {
+        let control = match self.workers.get(&id) {
+            Some((_, worker)) => match worker.send(control) {
+                Ok(()) => return,
+                Err(err) => err.0,
+            },
+            None => control,
+        };
+        let callback = match control {
+            Control::Resume(callback) => callback,
+            Control::Abort(callback) => callback,
+        };
+        reply(callback, Err(format!("The rollout {} is not running", id)));
+    }
+}
+
+impl ServiceSender {
+    pub fn start(&self, request: UpdateRolloutRequest, user_name: Option<String>) -> RPCResult<i64> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::Start(request, user_name, tx)).expect("Should success send request");
+        rx.recv().map_err(|err| RPCError::Internal(err.to_string()))?.map_err(RPCError::InvalidParams)
+    }
+
+    pub fn resume(&self, id: i64) -> RPCResult<()> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::Resume(id, tx)).expect("Should success send request");
+        rx.recv().map_err(|err| RPCError::Internal(err.to_string()))?.map_err(RPCError::InvalidParams)
+    }
+
+    pub fn abort(&self, id: i64) -> RPCResult<()> {
+        let (tx, rx) = channel();
+        self.sender.send(Message::Abort(id, tx)).expect("Should success send request");
+        rx.recv().map_err(|err| RPCError::Internal(err.to_string()))?.map_err(RPCError::InvalidParams)
+    }
+}
+
+/// Splits the nodes into the waves of `batch_size` nodes in the requested order.
+fn plan_waves(node_names: &[NodeName], batch_size: usize) -> Vec<RolloutNode> {
+    node_names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| RolloutNode {
+            name: name.clone(),
+            wave: index / batch_size,
+            status: RolloutNodeStatus::Pending,
+            message: None,
+            updated_at: None,
+        })
+        .collect()
+}
+
+/// Returns the new status of a node being updated, or None while it is still updating.
+fn check_node(
+    update: &UpdateCodeChainRequest,
+    client: Option<&ClientQueryResult>,
+    network_best_block: Option<i64>,
+) -> Option<(RolloutNodeStatus, Option<String>)> {
+    let client = client?;
+    match client.status {
+        NodeStatus::Error => return Some((RolloutNodeStatus::Error, Some("The node is in error".to_string()))),
+        NodeStatus::Run => {}
+        _ => return None,
+    }
+    if !client.version.as_ref().map_or(false, |version| update.is_applied(version)) {
+        return None
+    }
+    let best_block = client.best_block_id?.block_number;
+    if best_block + CATCH_UP_MARGIN < network_best_block.unwrap_or(best_block) {
+        return None
+    }
+    Some((RolloutNodeStatus::Done, None))
+}
+
+struct Aborted;
+
+struct Worker {
+    rollout: Rollout,
+    control: Receiver<Control>,
+    db_service: DBServiceSender,
+    node_control: NodeControl,
+    frontend_service: frontend::ServiceSender,
+}
+
+impl Worker {
+    fn run(&mut self) {
+        let waves = self.rollout.nodes.iter().map(|node| node.wave + 1).max().unwrap_or(0);
+        for wave in 0..waves {
+            if let Err(Aborted) = self.run_wave(wave) {
+                self.abort();
+                return
+            }
+        }
+        self.rollout.status = RolloutStatus::Completed;
+        cinfo!("Rollout {} is completed", self.rollout.id);
+        self.save();
+    }
+
+    fn run_wave(&mut self, wave: usize) -> Result<(), Aborted> {
+        self.handle_controls()?;
+
+        let update = self.rollout.request.update.clone();
+        for node in self.rollout.nodes.iter_mut().filter(|node| node.wave == wave) {
+            let result = self.node_control.update(&node.name, update.clone());
+            match result {
+                Ok(()) => set_status(node, RolloutNodeStatus::Updating, None),
+                Err(err) => set_status(node, RolloutNodeStatus::Error, Some(err.to_string())),
+            }
+        }
+        self.save();
+
+        let started_at = Instant::now();
+        while self.rollout.nodes.iter().any(|node| node.status == RolloutNodeStatus::Updating) {
+            thread::sleep(UPDATE_INTERVAL);
+            self.handle_controls()?;
+            if self.check_updating_nodes(started_at.elapsed() > NODE_UPDATE_TIMEOUT) {
+                self.save();
+            }
+        }
+
+        let failed = self.rollout.nodes.iter().any(|node| node.wave == wave && node.status == RolloutNodeStatus::Error);
+        if !failed {
+            return Ok(())
+        }
+        match self.rollout.request.on_error {
+            RolloutErrorPolicy::Abort => Err(Aborted),
+            RolloutErrorPolicy::Pause => self.pause(),
+        }
+    }
+
+    /// Returns whether any node is changed.
+    fn check_updating_nodes(&mut self, timed_out: bool) -> bool {
+        let clients = match self.db_service.get_clients_state() {
+            Ok(clients) => clients,
+            Err(err) => {
+                cwarn!("Rollout {} cannot get clients: {:?}", self.rollout.id, err);
+                return false
+            }
+        };
+        let network_best_block = clients
+            .iter()
+            .filter(|client| client.status == NodeStatus::Run)
+            .filter_map(|client| client.best_block_id.map(|best_block_id| best_block_id.block_number))
+            .max();
+
+        let mut changed = false;
+        let update = &self.rollout.request.update;
+        for node in self.rollout.nodes.iter_mut().filter(|node| node.status == RolloutNodeStatus::Updating) {
+            let client = clients.iter().find(|client| client.name == node.name);
+            match check_node(update, client, network_best_block) {
+                Some((status, message)) => set_status(node, status, message),
+                None if timed_out => set_status(
+                    node,
+                    RolloutNodeStatus::Error,
+                    Some("The node did not run the new version and catch up in time".to_string()),
+                ),
+                None => continue,
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    fn pause(&mut self) -> Result<(), Aborted> {
+        self.rollout.status = RolloutStatus::Paused;
+        cwarn!("Rollout {} is paused because of a failed node", self.rollout.id);
+        self.save();
+        loop {
+            match self.control.recv() {
+                Ok(Control::Resume(callback)) => {
+                    self.rollout.status = RolloutStatus::Running;
+                    cinfo!("Rollout {} is resumed", self.rollout.id);
+                    self.save();
+                    reply(callback, Ok(()));
+                    return Ok(())
+                }
+                Ok(Control::Abort(callback)) => {
+                    reply(callback, Ok(()));
+                    return Err(Aborted)
+                }
+                Err(_) => return Err(Aborted),
+            }
+        }
+    }
+
+    /// Handles the requests received while the rollout is running.
+    fn handle_controls(&mut self) -> Result<(), Aborted> {
+        loop {
+            match self.control.try_recv() {
+                Ok(Control::Resume(callback)) => reply(callback, Err("The rollout is not paused".to_string())),
+                Ok(Control::Abort(callback)) => {
+                    reply(callback, Ok(()));
+                    return Err(Aborted)
+                }
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => return Err(Aborted),
+            }
+        }
+    }
+
+    fn abort(&mut self) {
+        for node in &mut self.rollout.nodes {
+            match node.status {
+                RolloutNodeStatus::Pending => set_status(node, RolloutNodeStatus::Skipped, None),
+                RolloutNodeStatus::Updating => set_status(
+                    node,
+                    RolloutNodeStatus::Error,
+                    Some("The rollout is aborted while updating the node".to_string()),
+                ),
+                _ => {}
+            }
+        }
+        self.rollout.status = RolloutStatus::Aborted;
+        cwarn!("Rollout {} is aborted", self.rollout.id);
+        self.save();
+    }
+
+    /// Stores the progress and sends it to the frontends.
+    fn save(&mut self) {
+        self.rollout.updated_at = Utc::now();
+        self.db_service.update_rollout(self.rollout.clone());
+
+        let node_names = self.rollout.nodes.iter().map(|node| node.name.clone()).collect();
+        let message = jsonrpc::serialize_notification("rollout_updated", &self.rollout);
+        self.frontend_service
+            .send(frontend::Message::SendEvent(frontend::EventType::RolloutUpdated, node_names, message))
+            .expect("Should success send event");
+    }
+}
+
+fn set_status(node: &mut RolloutNode, status: RolloutNodeStatus, message: Option<String>) {
+    node.status = status;
+    node.message = message;
+    node.updated_at = Some(Utc::now());
+}
+
+fn reply(callback: Sender<Result<(), String>>, result: Result<(), String>) {
+    if let Err(callback_err) = callback.send(result) {
+        cerror!("Error at {}", callback_err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common_rpc_types::{BlockId, NodeVersion};
+    use primitives::H256;
+
+    #[test]
+    fn split_nodes_into_waves() {
+        let names: Vec<_> = ["a", "b", "c", "d", "e"].iter().map(|name| name.to_string()).collect();
+        let waves: Vec<_> = plan_waves(&names, 2).iter().map(|node| node.wave).collect();
+        assert_eq!(vec![0, 0, 1, 1, 2], waves);
+    }
+
+    #[test]
+    fn node_is_done_when_it_runs_new_version_and_catches_up() {
+        let update = UpdateCodeChainRequest::Git {
+            commit_hash: "new".to_string(),
+        };
+        let client = |status, hash: &str, block_number| ClientQueryResult {
+            name: "a".to_string(),
+            status,
+            address: None,
+            peers: Vec::new(),
+            best_block_id: Some(BlockId {
+                block_number,
+                hash: H256::from([1; 32]),
+            }),
+            version: Some(NodeVersion {
+                version: "1.0.0".to_string(),
+                hash: hash.to_string(),
+                binary_checksum: String::new(),
+            }),
+            pending_transactions: Vec::new(),
+            whitelist: None,
+            blacklist: None,
+            hardware: None,
+        };
+        let status =
+            |client: ClientQueryResult| check_node(&update, Some(&client), Some(100)).map(|(status, _)| status);
+
+        assert_eq!(None, status(client(NodeStatus::Starting, "new", 100)));
+        assert_eq!(None, status(client(NodeStatus::Run, "old", 100)));
+        assert_eq!(None, status(client(NodeStatus::Run, "new", 90)));
+        assert_eq!(Some(RolloutNodeStatus::Done), status(client(NodeStatus::Run, "new", 99)));
+        assert_eq!(Some(RolloutNodeStatus::Error), status(client(NodeStatus::Error, "old", 0)));
+    }
+}
@@ -1,4 +1,5 @@
 use super::rpc::{RPCError, RPCResponse};
+use crate::auth::Role;
 use serde::de::Deserialize;
 use serde::Serialize;
 use serde_json::{self, Value};
@@ -9,8 +10,26 @@ pub trait Route {
     fn run(&self, context: Self::Context, value: Value) -> RPCResponse<Value>;
 }
 
+/// The context of a request tells the role of the caller, which is checked before running a route.
+pub trait Authorize {
+    /// `None` if the caller is not logged in.
+    fn role(&self) -> Option<Role>;
+}
+
+/// Records the calls of the mutating routes.
+pub trait Audit {
+    fn audit(&self, method: &str, params: &Value, result: &RPCResponse<Value>);
+}
+
+struct Entry<C> {
+    /// A route without a role can be called before logging in.
+    role: Option<Role>,
+    mutating: bool,
+    route: Box<dyn Route<Context = C>>,
+}
+
 pub struct Router<C> {
-    table: HashMap<&'static str, Box<dyn Route<Context = C>>>,
+    table: HashMap<&'static str, Entry<C>>,
 }
 
 impl<Arg, Result, C> Route for fn(context: C, Arg) -> RPCResponse<Result>
@@ -53,24 +72,157 @@ pub enum Error {
 
 impl<C> Router<C> {
     pub fn new() -> Self {
-        let table: HashMap<&'static str, Box<dyn Route<Context = C>>> = HashMap::new();
+        let table: HashMap<&'static str, Entry<C>> = HashMap::new();
         Self {
             table,
         }
     }
 
-    pub fn add_route(&mut self, method: &'static str, route: Box<dyn Route<Context = C>>) {
-        self.table.insert(method, route);
+    /// The route can be called by the users whose role is the given role or higher.
+    pub fn add_route(&mut self, method: &'static str, role: Role, route: Box<dyn Route<Context = C>>) {
+        self.table.insert(method, Entry {
+            role: Some(role),
+            mutating: false,
+            route,
+        });
     }
 
+    /// Same as `add_route`, but every call of the route is audited.
+    pub fn add_mutating_route(&mut self, method: &'static str, role: Role, route: Box<dyn Route<Context = C>>) {
+        self.table.insert(method, Entry {
+            role: Some(role),
+            mutating: true,
+            route,
+        });
+    }
+
+    pub fn add_public_route(&mut self, method: &'static str, route: Box<dyn Route<Context = C>>) {
+        self.table.insert(method, Entry {
+            role: None,
+            mutating: false,
+            route,
+        });
+    }
+}
+
+impl<C: Authorize + Audit + Clone> Router<C> {
+    /// The calls of the mutating routes are audited even if they are rejected by the role.
     pub fn run(&self, context: C, method: &str, arg: Value) -> Result<Option<Value>, Error> {
-        let route = self.table.get(method);
-        match route {
-            None => Err(Error::MethodNotFound),
-            Some(route) => match route.run(context, arg) {
-                Ok(value) => Ok(value),
-                Err(err) => Err(Error::RPC(err)),
-            },
+        let entry = self.table.get(method).ok_or(Error::MethodNotFound)?;
+        if let Err(err) = Self::authorize(&context, entry.role) {
+            let result = Err(err);
+            if entry.mutating {
+                context.audit(method, &arg, &result);
+            }
+            return result.map_err(Error::RPC)
+        }
+        if !entry.mutating {
+            return entry.route.run(context, arg).map_err(Error::RPC)
+        }
+
+        let result = entry.route.run(context.clone(), arg.clone());
+        context.audit(method, &arg, &result);
+        result.map_err(Error::RPC)
+    }
+
+    fn authorize(context: &C, required_role: Option<Role>) -> Result<(), RPCError> {
+        let required_role = match required_role {
+            Some(required_role) => required_role,
+            None => return Ok(()),
+        };
+        match context.role() {
+            None => Err(RPCError::Unauthenticated),
+            Some(role) if role < required_role => Err(RPCError::PermissionDenied),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rpc::response;
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[derive(Clone, Default)]
+    struct TestContext {
+        role: Option<Role>,
+        audited: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl TestContext {
+        fn with_role(role: Role) -> Self {
+            TestContext {
+                role: Some(role),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Authorize for TestContext {
+        fn role(&self) -> Option<Role> {
+            self.role
         }
     }
+
+    impl Audit for TestContext {
+        fn audit(&self, method: &str, _params: &Value, _result: &RPCResponse<Value>) {
+            self.audited.borrow_mut().push(method.to_string());
+        }
+    }
+
+    fn ok(_: TestContext) -> RPCResponse<String> {
+        response("ok".to_string())
+    }
+
+    fn router() -> Router<TestContext> {
+        let mut router = Router::new();
+        router.add_public_route("public", Box::new(ok as fn(TestContext) -> RPCResponse<String>));
+        router.add_route("read", Role::Viewer, Box::new(ok as fn(TestContext) -> RPCResponse<String>));
+        router.add_mutating_route("write", Role::Operator, Box::new(ok as fn(TestContext) -> RPCResponse<String>));
+        router
+    }
+
+    #[test]
+    fn public_route_does_not_need_login() {
+        assert!(router().run(TestContext::default(), "public", Value::Null).is_ok());
+    }
+
+    #[test]
+    fn route_needs_login() {
+        let result = router().run(TestContext::default(), "read", Value::Null);
+        assert!(matches!(result, Err(Error::RPC(RPCError::Unauthenticated))));
+    }
+
+    #[test]
+    fn route_needs_role() {
+        let router = router();
+        assert!(router.run(TestContext::with_role(Role::Viewer), "read", Value::Null).is_ok());
+        let result = router.run(TestContext::with_role(Role::Viewer), "write", Value::Null);
+        assert!(matches!(result, Err(Error::RPC(RPCError::PermissionDenied))));
+        assert!(router.run(TestContext::with_role(Role::Operator), "write", Value::Null).is_ok());
+    }
+
+    #[test]
+    fn only_mutating_route_is_audited() {
+        let router = router();
+        let context = TestContext::with_role(Role::Operator);
+        router.run(context.clone(), "read", Value::Null).ok();
+        router.run(context.clone(), "write", Value::Null).ok();
+        assert_eq!(vec!["write".to_string()], *context.audited.borrow());
+    }
+
+    #[test]
+    fn denied_call_of_mutating_route_is_audited() {
+        let router = router();
+        let viewer = TestContext::with_role(Role::Viewer);
+        router.run(viewer.clone(), "write", Value::Null).ok();
+        assert_eq!(vec!["write".to_string()], *viewer.audited.borrow());
+
+        let anonymous = TestContext::default();
+        router.run(anonymous.clone(), "write", Value::Null).ok();
+        router.run(anonymous.clone(), "read", Value::Null).ok();
+        assert_eq!(vec!["write".to_string()], *anonymous.audited.borrow());
+    }
 }
@@ -16,6 +16,15 @@ pub enum RPCError {
     FromDB(DBError),
 
     ClientNotFound,
+    InvalidParams(String),
+    /// The login failed or the session is not valid.
+    Unauthenticated,
+    /// The role of the session cannot call the method.
+    PermissionDenied,
+    /// The address failed to log in too many times.
+    TooManyFailedLogins,
+    /// The connection or the server is running too many log exports.
+    TooManyLogExports,
 }
 
 impl fmt::Display for RPCError {
@@ -25,6 +34,11 @@ impl fmt::Display for RPCError {
             RPCError::FromClient(err) => write!(f, "JSONRPCError from Client {:?}", err),
             RPCError::FromDB(err) => write!(f, "JSONRPCError from DB {:?}", err),
             RPCError::ClientNotFound => write!(f, "Client not found"),
+            RPCError::InvalidParams(err) => write!(f, "Invalid params: {}", err),
+            RPCError::Unauthenticated => write!(f, "Authentication failed"),
+            RPCError::PermissionDenied => write!(f, "Permission denied"),
+            RPCError::TooManyFailedLogins => write!(f, "Too many failed logins, try again later"),
+            RPCError::TooManyLogExports => write!(f, "Too many log exports are running, try again later"),
         }
     }
 }
@@ -34,6 +48,10 @@ pub fn response<T>(value: T) -> RPCResponse<T> {
 }
 
 const ERR_AGENT_NOT_FOUND: i64 = -1;
+const ERR_UNAUTHENTICATED: i64 = -2;
+const ERR_PERMISSION_DENIED: i64 = -3;
+const ERR_TOO_MANY_FAILED_LOGINS: i64 = -4;
+const ERR_TOO_MANY_LOG_EXPORTS: i64 = -5;
 
 impl From<RPCError> for JSONRPCError {
     fn from(err: RPCError) -> Self {
@@ -51,6 +69,11 @@ impl From<RPCError> for JSONRPCError {
             }
             RPCError::FromDB(_) => RPCError::create_internal_rpc_error(err.to_string()),
             RPCError::ClientNotFound => RPCError::create_rpc_error(ERR_AGENT_NOT_FOUND, err.to_string()),
+            RPCError::InvalidParams(msg) => JSONRPCError::invalid_params(msg),
+            RPCError::Unauthenticated => RPCError::create_rpc_error(ERR_UNAUTHENTICATED, err.to_string()),
+            RPCError::PermissionDenied => RPCError::create_rpc_error(ERR_PERMISSION_DENIED, err.to_string()),
+            RPCError::TooManyFailedLogins => RPCError::create_rpc_error(ERR_TOO_MANY_FAILED_LOGINS, err.to_string()),
+            RPCError::TooManyLogExports => RPCError::create_rpc_error(ERR_TOO_MANY_LOG_EXPORTS, err.to_string()),
         }
     }
 }
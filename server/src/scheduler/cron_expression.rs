@@ -0,0 +1,177 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+use std::str::FromStr;
+
+/// How far `next_after` looks for a matching time, so that an expression such as "0 0 30 2 *" doesn't loop forever.
+const SEARCH_DAYS: i64 = 366 * 5;
+
+/// A cron expression of five fields: minute, hour, day of month, month and day of week, evaluated in UTC.
+/// A field is `*`, a number, a range `a-b`, a step `*/n` or `a-b/n`, or a list of them separated by commas.
+/// Sunday is 0 or 7 in the day of week.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpression {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// When both the day of month and the day of week are restricted, a day matching either of them is selected.
+    any_day: bool,
+}
+
+impl FromStr for CronExpression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("A cron expression should have 5 fields, but {:?} has {}", s, fields.len()))
+        }
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(CronExpression {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            any_day: !fields[2].starts_with('*') && !fields[4].starts_with('*'),
+        })
+    }
+}
+
+impl CronExpression {
+    /// Returns the first matching time later than `time`.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        for offset in 0..SEARCH_DAYS {
+            let date = start.date() + Duration::days(offset);
+            if !self.matches_date(date.day(), date.month(), date.weekday().num_days_from_sunday()) {
+                continue
+            }
+            let first_hour = if offset == 0 {
+                start.hour()
+            } else {
+                0
+            };
+            for hour in (first_hour..24).filter(|hour| has_bit(self.hours, *hour)) {
+                let first_minute = if offset == 0 && hour == start.hour() {
+                    start.minute()
+                } else {
+                    0
+                };
+                if let Some(minute) = (first_minute..60).find(|minute| has_bit(self.minutes, *minute)) {
+                    return Some(Utc.ymd(date.year(), date.month(), date.day()).and_hms(hour, minute, 0))
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_date(&self, day: u32, month: u32, weekday: u32) -> bool {
+        if !has_bit(self.months, month) {
+            return false
+        }
+        let day_matches = has_bit(self.days, day);
+        let weekday_matches = has_bit(self.weekdays, weekday);
+        if self.any_day {
+            day_matches || weekday_matches
+        } else {
+            day_matches && weekday_matches
+        }
+    }
+}
+
+fn has_bit(bits: u64, index: u32) -> bool {
+    bits & (1 << index) != 0
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(index) => (&part[..index], Some(parse_number(&part[index + 1..])?)),
+            None => (part, None),
+        };
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else if let Some(index) = range.find('-') {
+            (parse_number(&range[..index])?, parse_number(&range[index + 1..])?)
+        } else {
+            // "a/n" means from a to the maximum
+            let from = parse_number(range)?;
+            let to = if step.is_some() {
+                max
+            } else {
+                from
+            };
+            (from, to)
+        };
+        if from < min || to > max || from > to {
+            return Err(format!("{:?} is out of the range {}-{}", part, min, max))
+        }
+        let step = step.unwrap_or(1);
+        if step == 0 {
+            return Err(format!("The step of {:?} should be positive", part))
+        }
+        for value in (from..=to).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn parse_number(s: &str) -> Result<u32, String> {
+    s.parse().map_err(|_| format!("{:?} is not a number", s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn next(expression: &str, after: &str) -> DateTime<Utc> {
+        expression.parse::<CronExpression>().unwrap().next_after(time(after)).unwrap()
+    }
+
+    #[test]
+    fn next_of_daily_expression() {
+        assert_eq!(time("2019-05-01T03:00:00Z"), next("0 3 * * *", "2019-04-30T03:00:00Z"));
+        assert_eq!(time("2019-04-30T03:00:00Z"), next("0 3 * * *", "2019-04-30T02:59:59Z"));
+    }
+
+    #[test]
+    fn next_of_steps_and_lists() {
+        assert_eq!(time("2019-04-30T10:15:00Z"), next("*/15 * * * *", "2019-04-30T10:01:00Z"));
+        assert_eq!(time("2019-04-30T18:00:00Z"), next("0 9,18 * * *", "2019-04-30T09:00:00Z"));
+        assert_eq!(time("2019-05-01T09:00:00Z"), next("0 9-17/4 * * *", "2019-04-30T17:00:00Z"));
+    }
+
+    #[test]
+    fn next_of_weekdays() {
+        // 2019-04-30 is Tuesday.
+        assert_eq!(time("2019-05-04T00:00:00Z"), next("0 0 * * 6", "2019-04-30T00:00:00Z"));
+        assert_eq!(time("2019-05-05T00:00:00Z"), next("0 0 * * 7", "2019-04-30T00:00:00Z"));
+        // Either the 1st day or Saturday
+        assert_eq!(time("2019-05-01T00:00:00Z"), next("0 0 1 * 6", "2019-04-30T00:00:00Z"));
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        assert!("0 3 * *".parse::<CronExpression>().is_err());
+        assert!("60 * * * *".parse::<CronExpression>().is_err());
+        assert!("*/0 * * * *".parse::<CronExpression>().is_err());
+        assert!("a * * * *".parse::<CronExpression>().is_err());
+        assert!("0 0 0 * *".parse::<CronExpression>().is_err());
+    }
+
+    #[test]
+    fn impossible_date_has_no_next() {
+        let expression: CronExpression = "0 0 30 2 *".parse().unwrap();
+        assert_eq!(None, expression.next_after(time("2019-04-30T00:00:00Z")));
+    }
+}
@@ -0,0 +1,125 @@
+mod cron_expression;
+
+pub use self::cron_expression::CronExpression;
+
+use super::client::ServiceSender as ClientServiceSender;
+use super::common_rpc_types::{Job, JobRun, JobSchedule};
+use super::db::ServiceSender as DBServiceSender;
+use super::node_control::NodeControl;
+use chrono::{DateTime, Duration, Utc};
+use std::thread;
+
+/// How often the scheduler looks for the jobs to run
+const TICK: std::time::Duration = std::time::Duration::from_secs(10);
+/// A run delayed longer than this, e.g. while the server was stopped, is skipped and recorded as missed.
+const MISSED_RUN_TOLERANCE_MINUTES: i64 = 10;
+
+pub fn start(
+    db_service: DBServiceSender,
+    client_service: ClientServiceSender,
+    node_control_concurrency: usize,
+) -> thread::JoinHandle<()> {
+    let node_control = NodeControl::new(client_service, db_service.clone(), node_control_concurrency);
+    thread::Builder::new()
+        .name("scheduler".to_string())
+        .spawn(move || loop {
+            run_due_jobs(&db_service, &node_control, Utc::now());
+            thread::sleep(TICK);
+        })
+        .expect("Should success running scheduler thread")
+}
+
+/// Returns the first time of the schedule later than `after`, or None if the job will not run again.
+pub fn next_run_at(schedule: &JobSchedule, after: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, String> {
+    match schedule {
+        JobSchedule::Once {
+            at,
+        } => Ok(Some(*at).filter(|at| *at > after)),
+        JobSchedule::Cron {
+            expression,
+        } => Ok(expression.parse::<CronExpression>()?.next_after(after)),
+    }
+}
+
+fn run_due_jobs(db_service: &DBServiceSender, node_control: &NodeControl, now: DateTime<Utc>) {
+    let jobs = match db_service.get_jobs() {
+        Ok(jobs) => jobs,
+        Err(err) => {
+            cwarn!("Scheduler cannot get jobs: {:?}", err);
+            return
+        }
+    };
+    for mut job in jobs {
+        let scheduled_at = match job.next_run_at {
+            Some(scheduled_at) if job.enabled && scheduled_at <= now => scheduled_at,
+            _ => continue,
+        };
+
+        // The next time is stored before running, so that a job runs once even if it takes longer than a tick.
+        // The job doesn't run if it was disabled or deleted after it was read.
+        job.next_run_at = next_run_at(&job.schedule, now).unwrap_or_else(|err| {
+            cerror!("The schedule of job {} is invalid: {}", job.id, err);
+            None
+        });
+        job.enabled = job.next_run_at.is_some();
+        match db_service.claim_job(job.clone(), scheduled_at) {
+            Ok(true) => {}
+            Ok(false) => {
+                cinfo!("Job {} ({}) was changed before its run at {}", job.id, job.name, scheduled_at);
+                continue
+            }
+            Err(err) => {
+                cerror!("Cannot update job {}: {:?}", job.id, err);
+                continue
+            }
+        }
+
+        if now - scheduled_at > Duration::minutes(MISSED_RUN_TOLERANCE_MINUTES) {
+            cwarn!("Job {} ({}) missed the run at {}", job.id, job.name, scheduled_at);
+            db_service.write_job_run(JobRun {
+                id: 0,
+                job_id: job.id,
+                job_name: job.name,
+                started_at: now,
+                finished_at: now,
+                results: Vec::new(),
+                error: Some(format!("Missed the run at {}", scheduled_at)),
+            });
+            continue
+        }
+
+        let db_service = db_service.clone();
+        let node_control = node_control.clone();
+        thread::Builder::new()
+            .name(format!("job {}", job.id))
+            .spawn(move || run_job(&db_service, &node_control, job))
+            .expect("Should success running job thread");
+    }
+}
+
+fn run_job(db_service: &DBServiceSender, node_control: &NodeControl, job: Job) {
+    cinfo!("Job {} ({}) is started", job.id, job.name);
+    let started_at = Utc::now();
+    let (results, error) = match node_control.select(job.nodes.clone()) {
+        Ok(names) => {
+            let action = job.action.clone();
+            (node_control.run(names, move |node_control, name| node_control.apply(name, &action)), None)
+        }
+        Err(err) => (Vec::new(), Some(err.to_string())),
+    };
+
+    let failed: Vec<&str> =
+        results.iter().filter(|result| result.error.is_some()).map(|result| result.name.as_str()).collect();
+    if error.is_some() || !failed.is_empty() {
+        cwarn!("Job {} ({}) failed on {:?}: {:?}", job.id, job.name, failed, error);
+    }
+    db_service.write_job_run(JobRun {
+        id: 0,
+        job_id: job.id,
+        job_name: job.name,
+        started_at,
+        finished_at: Utc::now(),
+        results,
+        error,
+    });
+}
@@ -0,0 +1,32 @@
+use crate::config::TlsConfig;
+use openssl::error::ErrorStack;
+use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod, SslStream};
+use std::net::SocketAddr;
+use ws::util::TcpStream;
+use ws::{Error as WSError, ErrorKind, Factory, Settings};
+
+pub fn acceptor(config: &TlsConfig) -> Result<SslAcceptor, ErrorStack> {
+    let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls())?;
+    builder.set_private_key_file(&config.private_key, SslFiletype::PEM)?;
+    builder.set_certificate_chain_file(&config.certificate)?;
+    builder.check_private_key()?;
+    Ok(builder.build())
+}
+
+/// Same as `ws::listen`, but the connections are encrypted if `encrypt` is true.
+/// The handlers should accept the TLS connections in `Handler::upgrade_ssl_server`.
+pub fn listen<F: Factory>(address: SocketAddr, encrypt: bool, factory: F) -> ws::Result<()> {
+    let settings = Settings {
+        encrypt_server: encrypt,
+        ..Settings::default()
+    };
+    ws::Builder::new().with_settings(settings).build(factory)?.listen(address)?;
+    Ok(())
+}
+
+pub fn upgrade(acceptor: Option<&SslAcceptor>, sock: TcpStream) -> ws::Result<SslStream<TcpStream>> {
+    match acceptor {
+        Some(acceptor) => acceptor.accept(sock).map_err(From::from),
+        None => Err(WSError::new(ErrorKind::Internal, "TLS is not configured")),
+    }
+}
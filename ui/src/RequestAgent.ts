@@ -1,4 +1,5 @@
 import { toast } from "react-toastify";
+import { setLoggedIn, setLoggedOut } from "./actions/auth";
 import { updateChainNetworks } from "./actions/chainNetworks";
 import { updateNodeInfo } from "./actions/nodeInfo";
 import {
@@ -8,11 +9,20 @@ import {
 } from "./requests/types";
 const WebSocket = require("rpc-websockets").Client;
 
+const SESSION_TOKEN_KEY = "sessionToken";
+const SESSION_USER_KEY = "sessionUserName";
+
 export interface JsonRPCError {
   code: number;
   message: string;
 }
 
+interface AuthLoginResponse {
+  token: string;
+  role: "viewer" | "operator";
+  expiresAt: string;
+}
+
 export default class RequestAgent {
   public static getInstance = () => {
     return RequestAgent.instance;
@@ -23,34 +33,30 @@ export default class RequestAgent {
   private agentHubHost = process.env.REACT_APP_AGENT_HUB_HOST
     ? process.env.REACT_APP_AGENT_HUB_HOST
     : "ws://localhost:3012";
-  private passphrase = process.env.REACT_APP_AGENT_HUB_PASSPHRASE
-    ? process.env.REACT_APP_AGENT_HUB_PASSPHRASE
-    : "passphrase";
   private isConnected: boolean = false;
+  private isLoggedIn: boolean = false;
   constructor() {
     console.log("Create websocket");
-    this.ws = new WebSocket(this.agentHubHost + `/${this.passphrase}`);
+    // The session token is sent in a message, not in the URL,
+    // to keep it out of the access logs.
+    this.ws = new WebSocket(this.agentHubHost);
     this.ws.on("open", () => {
       console.log("connected");
       this.isConnected = true;
-      this.ws
-        .subscribe(["dashboard_updated", "node_updated"])
-        .catch((e: any) => {
-          console.log(e);
-        });
-
-      this.ws.on("dashboard_updated", (e: ChainNetworksUpdate) => {
-        this.dispatch(updateChainNetworks(e));
-      });
-
-      this.ws.on("node_updated", (e: NodeUpdateInfo) => {
-        this.dispatch(updateNodeInfo(e.name, e));
-      });
+      this.resumeSession();
+    });
+    this.ws.on("dashboard_updated", (e: ChainNetworksUpdate) => {
+      this.dispatch(updateChainNetworks(e));
+    });
+    this.ws.on("node_updated", (e: NodeUpdateInfo) => {
+      this.dispatch(updateNodeInfo(e.name, e));
     });
     this.ws.on("error", (e: any) => {
       console.log("error", e);
     });
     this.ws.on("close", () => {
+      this.isConnected = false;
+      this.isLoggedIn = false;
       toast.error("Agent hub is closed.");
       console.log("closed");
     });
@@ -58,12 +64,22 @@ export default class RequestAgent {
   public setDispatch = (dispatch: any) => {
     this.dispatch = dispatch;
   };
+  public login = async (name: string, password: string) => {
+    await this.ensureConnection();
+    const response: AuthLoginResponse = await this.ws.call("auth_login", [
+      name,
+      password
+    ]);
+    sessionStorage.setItem(SESSION_TOKEN_KEY, response.token);
+    this.onLoggedIn(name, response.role);
+  };
   public call = async <T>(
     method: string,
     params: object | Array<object>
   ): Promise<T> => {
     try {
       await this.ensureConnection();
+      await this.ensureLogin();
     } catch (e) {
       toast.error("Agent hub is not responding.");
       throw e;
@@ -81,8 +97,48 @@ export default class RequestAgent {
   public close = () => {
     this.ws.close();
   };
+  // Resumes the session saved by the last login,
+  // so that a reload or a reconnection doesn't need the password.
+  private resumeSession = async () => {
+    const token = sessionStorage.getItem(SESSION_TOKEN_KEY);
+    if (!token) {
+      this.dispatch(setLoggedOut());
+      return;
+    }
+    try {
+      const response: AuthLoginResponse = await this.ws.call("auth_resume", [
+        token
+      ]);
+      const userName = sessionStorage.getItem(SESSION_USER_KEY);
+      this.onLoggedIn(userName || "", response.role);
+    } catch (e) {
+      this.onLoggedOut();
+    }
+  };
+  private onLoggedIn = (name: string, role: "viewer" | "operator") => {
+    sessionStorage.setItem(SESSION_USER_KEY, name);
+    this.isLoggedIn = true;
+    this.ws
+      .subscribe(["dashboard_updated", "node_updated"])
+      .catch((e: any) => {
+        console.log(e);
+      });
+    this.dispatch(setLoggedIn(name, role));
+  };
+  private onLoggedOut = () => {
+    sessionStorage.removeItem(SESSION_TOKEN_KEY);
+    this.isLoggedIn = false;
+    this.dispatch(setLoggedOut());
+  };
   private handleCommonError = (e: JsonRPCError) => {
     switch (e.code) {
+      case CommonError.Unauthenticated:
+        toast.error("The session is expired.");
+        this.onLoggedOut();
+        return true;
+      case CommonError.PermissionDenied:
+        toast.error("Permission denied");
+        return true;
       case CommonError.AgentNotFound:
         toast.error("Agent not found");
         return true;
@@ -112,4 +168,15 @@ export default class RequestAgent {
       })();
     });
   };
+  // Waits for the login screen or the saved session
+  private ensureLogin = () => {
+    return new Promise(resolve => {
+      (function waitForLogin() {
+        if (RequestAgent.getInstance().isLoggedIn) {
+          return resolve();
+        }
+        setTimeout(waitForLogin, 50);
+      })();
+    });
+  };
 }
@@ -0,0 +1,24 @@
+export type AuthAction = SetLoggedIn | SetLoggedOut;
+
+export interface SetLoggedIn {
+  type: "SetLoggedIn";
+  userName: string;
+  role: "viewer" | "operator";
+}
+
+export interface SetLoggedOut {
+  type: "SetLoggedOut";
+}
+
+export const setLoggedIn = (
+  userName: string,
+  role: "viewer" | "operator"
+) => ({
+  type: "SetLoggedIn",
+  userName,
+  role
+});
+
+export const setLoggedOut = () => ({
+  type: "SetLoggedOut"
+});
@@ -10,11 +10,18 @@ import { GlobalNavigationBar } from "../GlobalNavigationBar/GlobalNavigationBar"
 import Graph from "../Graph/Graph";
 import { Header } from "../Header/Header";
 import Log from "../Log/Log";
+import Login from "../Login/Login";
 import NodeList from "../NodeList/NodeList";
 import RPC from "../RPC/RPC";
+import { ReducerConfigure } from "../../reducers";
 import "./App.css";
 
-class App extends React.Component<DispatchProp> {
+interface StateProps {
+  isLoggedIn: boolean | undefined;
+}
+
+type Props = StateProps & DispatchProp;
+class App extends React.Component<Props> {
   public componentDidMount() {
     if (process.env.NODE_ENV !== "test") {
       ReactModal.setAppElement("#app");
@@ -27,6 +34,15 @@ class App extends React.Component<DispatchProp> {
     RequestAgent.getInstance().close();
   }
   public render() {
+    const { isLoggedIn } = this.props;
+    if (!isLoggedIn) {
+      return (
+        <div id="app" className="app">
+          {isLoggedIn === false ? <Login /> : null}
+          <ToastContainer autoClose={false} />
+        </div>
+      );
+    }
     return (
       <Router>
         <div id="app" className="app">
@@ -45,4 +61,7 @@ class App extends React.Component<DispatchProp> {
     );
   }
 }
-export default connect()(App);
+const mapStateToProps = (state: ReducerConfigure) => ({
+  isLoggedIn: state.authReducer.isLoggedIn
+});
+export default connect(mapStateToProps)(App);
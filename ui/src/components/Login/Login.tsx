@@ -0,0 +1,89 @@
+import * as React from "react";
+import { Form, Label } from "reactstrap";
+import RequestAgent from "../../RequestAgent";
+import { CommonError } from "../../requests/types";
+import "./Login.css";
+
+interface State {
+  name: string;
+  password: string;
+  isRequesting: boolean;
+  error?: string | null;
+}
+
+export default class Login extends React.Component<{}, State> {
+  public constructor(props: {}) {
+    super(props);
+    this.state = {
+      name: "",
+      password: "",
+      isRequesting: false
+    };
+  }
+  public render() {
+    const { name, password, isRequesting, error } = this.state;
+    return (
+      <div className="login-container">
+        <Form className="login-form animated fadeIn" onSubmit={this.onSubmit}>
+          <h4 className="mb-4">CodeChain Dashboard</h4>
+          <div className="form-group">
+            <Label for="login-name-input">Name</Label>
+            <input
+              type="text"
+              className="form-control"
+              id="login-name-input"
+              autoComplete="username"
+              onChange={this.handleNameChange}
+              value={name}
+            />
+          </div>
+          <div className="form-group">
+            <Label for="login-password-input">Password</Label>
+            <input
+              type="password"
+              className="form-control"
+              id="login-password-input"
+              autoComplete="current-password"
+              onChange={this.handlePasswordChange}
+              value={password}
+            />
+          </div>
+          {error ? <p className="login-error">{error}</p> : null}
+          <button
+            type="submit"
+            className="btn btn-primary w-100"
+            disabled={isRequesting || !name || !password}
+          >
+            Log in
+          </button>
+        </Form>
+      </div>
+    );
+  }
+
+  private onSubmit = async (e: any) => {
+    e.preventDefault();
+    const { name, password } = this.state;
+    this.setState({ isRequesting: true, error: null });
+    try {
+      await RequestAgent.getInstance().login(name, password);
+    } catch (err) {
+      this.setState({
+        isRequesting: false,
+        password: "",
+        error:
+          err.code === CommonError.TooManyFailedLogins
+            ? "Too many failed logins. Try again later."
+            : "The name or the password is wrong."
+      });
+    }
+  };
+
+  private handleNameChange = (event: any) => {
+    this.setState({ name: event.target.value });
+  };
+
+  private handlePasswordChange = (event: any) => {
+    this.setState({ password: event.target.value });
+  };
+}
@@ -0,0 +1,34 @@
+import { AuthAction } from "../actions/auth";
+
+export interface AuthState {
+  // undefined until the saved session is checked
+  isLoggedIn: boolean | undefined;
+  userName?: string | null;
+  role?: "viewer" | "operator" | null;
+}
+
+const initialState: AuthState = {
+  isLoggedIn: undefined
+};
+
+export const authReducer = (state = initialState, action: AuthAction) => {
+  switch (action.type) {
+    case "SetLoggedIn": {
+      return {
+        ...state,
+        isLoggedIn: true,
+        userName: action.userName,
+        role: action.role
+      };
+    }
+    case "SetLoggedOut": {
+      return {
+        ...state,
+        isLoggedIn: false,
+        userName: null,
+        role: null
+      };
+    }
+  }
+  return state;
+};
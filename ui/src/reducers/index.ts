@@ -1,10 +1,12 @@
 import { combineReducers } from "redux";
+import { authReducer, AuthState } from "./auth";
 import { chainNetworksReducer, ChainNetworksState } from "./chainNetworks";
 import { graphReducer, GraphState } from "./graph";
 import { logReducer, LogState } from "./log";
 import { nodeInfoReducer, NodeState } from "./nodeInfo";
 
 export interface ReducerConfigure {
+  authReducer: AuthState;
   nodeInfoReducer: NodeState;
   chainNetworksReducer: ChainNetworksState;
   logReducer: LogState;
@@ -12,6 +14,7 @@ export interface ReducerConfigure {
 }
 
 const rootReducer = combineReducers({
+  authReducer,
   nodeInfoReducer,
   chainNetworksReducer,
   logReducer,
@@ -16,6 +16,10 @@ export interface WhiteList {
 export enum CommonError {
   CodeChainIsNotRunning = 0,
   AgentNotFound = -1,
+  Unauthenticated = -2,
+  PermissionDenied = -3,
+  TooManyFailedLogins = -4,
+  TooManyLogExports = -5,
   InternalError = -32603
 }
 export interface NetworkNodeInfo {